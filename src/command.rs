@@ -1,44 +1,99 @@
 #![allow(clippy::must_use_candidate)]
 #![allow(clippy::module_name_repetitions)]
+#![allow(clippy::enum_variant_names)]
 
 use crate::command::help::Help;
 use crate::command::publish::Publish;
 use crate::config::Config;
-use std::{env};
 use std::env::Args;
+use crate::config::PARAMETER_PREFIX;
 use std::fmt::{Display, Formatter};
 use chrono::ParseError;
 use thiserror::Error;
+use crate::command::args::{COMMANDS, CommandSpec, find_command, suggest};
+use crate::command::build::Build;
+use crate::command::compress::Compress;
+use crate::command::config::check::Check;
+use crate::command::config::show::Show;
 use crate::command::deploy::Deploy;
+use crate::command::deploy::history::History;
+use crate::command::deploy::rollback::Rollback;
+use crate::command::deploy::unlock::Unlock;
+use crate::command::doctor::Doctor;
 use crate::command::index::Index;
+use crate::command::list::List;
+use crate::command::release::Release;
+use crate::command::serve::Serve;
+use crate::command::stats::Stats;
+use crate::command::unpublish::Unpublish;
+use crate::command::edit::Edit;
+use crate::command::rename::Rename;
 use crate::command::version::Version;
+use crate::command::watch::Watch;
 
+pub mod args;
+pub mod build;
+pub mod compress;
+pub mod config;
 pub mod help;
 pub mod index;
+pub mod list;
 pub mod publish;
+pub mod release;
+pub mod serve;
+pub mod stats;
+pub mod unpublish;
+pub mod edit;
+pub mod rename;
 pub mod deploy;
+pub mod doctor;
 pub mod version;
+pub mod watch;
 
 pub const INDEX_COMMAND_NAME: &str = "index";
 pub const PUBLISH_COMMAND_NAME: &str = "publish";
 pub const HELP_COMMAND_NAME: &str = "help";
 pub const DEPLOY_COMMAND_NAME: &str = "deploy";
 pub const VERSION_COMMAND_NAME: &str = "version";
+pub const COMPRESS_COMMAND_NAME: &str = "compress";
+pub const BUILD_COMMAND_NAME: &str = "build";
+pub const RELEASE_COMMAND_NAME: &str = "release";
+pub const WATCH_COMMAND_NAME: &str = "watch";
+pub const SERVE_COMMAND_NAME: &str = "serve";
+pub const CONFIG_COMMAND_NAME: &str = "config";
+pub const DOCTOR_COMMAND_NAME: &str = "doctor";
+pub const LIST_COMMAND_NAME: &str = "list";
+pub const STATS_COMMAND_NAME: &str = "stats";
+pub const UNPUBLISH_COMMAND_NAME: &str = "unpublish";
+pub const EDIT_COMMAND_NAME: &str = "edit";
+pub const RENAME_COMMAND_NAME: &str = "rename";
+
+pub const HISTORY_SUBCOMMAND_NAME: &str = "history";
+pub const ROLLBACK_SUBCOMMAND_NAME: &str = "rollback";
+pub const UNLOCK_SUBCOMMAND_NAME: &str = "unlock";
+pub const SHOW_SUBCOMMAND_NAME: &str = "show";
+pub const CHECK_SUBCOMMAND_NAME: &str = "check";
+
+pub const DEPLOY_HISTORY_COMMAND_NAME: &str = "deploy history";
+pub const DEPLOY_ROLLBACK_COMMAND_NAME: &str = "deploy rollback";
+pub const DEPLOY_UNLOCK_COMMAND_NAME: &str = "deploy unlock";
+pub const CONFIG_SHOW_COMMAND_NAME: &str = "config show";
+pub const CONFIG_CHECK_COMMAND_NAME: &str = "config check";
 
 /// Список ошибок
 #[derive(Error, Debug)]
 pub enum Error {
     // config
-    #[error("Check parameter format, please. Should be --param-name or --param-name=value")]
-    Parse(),
     #[error("Check date time format `{0}`")]
     DateTimeError(ParseError),
     #[error("Value for {0} should be filled (not empty)")]
     EmptyValue(String),
     #[error("Value for {0} is too long, example: {1}. Expected less than {2}")]
     ValueTooLong(String, String, usize),
-    #[error("Env variable {0:?}")]
-    EnvVar(#[from] env::VarError),
+    #[error("Env variable {0} is not set")]
+    EnvVar(String),
+    #[error("Can't get secret: {0}")]
+    Secret(String),
     // deserializer
     #[error("Have no clue how process about {0} key")]
     UnknownKey(String),
@@ -56,8 +111,39 @@ pub enum Error {
     WriteFile(std::io::Error),
     #[error("Can't read dir {0:?}")]
     ReadDir(std::io::Error),
+    #[error("Can't listen on port {0}: {1}")]
+    Listen(u16, std::io::Error),
     #[error("Incorrect format. {0:?}")]
-    IncorrectFormat(String)
+    IncorrectFormat(String),
+    #[error("Unknown command `{0}`. {1}")]
+    UnknownCommand(String, String),
+    #[error("Unknown flag `{0}` for `{1}`. {2}")]
+    UnknownFlag(String, String, String),
+    #[error("Unexpected argument `{0}` for `{1}`. Flags should look like --flag or --flag=value")]
+    UnexpectedArgument(String, String),
+    #[error("Refusing to delete {0} of {1} remote objects (more than {2}%). Use --force to delete anyway")]
+    PruneLimit(usize, usize, u64),
+    #[error("Build command `{0}` failed: {1}")]
+    BuildFailed(String, String),
+    #[error("Build dir {0} was not refreshed by the build command")]
+    BuildNotRefreshed(String),
+    #[error("Release stopped at `{0}` step (completed: {1}): {2}")]
+    ReleaseFailed(String, String, Box<Error>),
+    #[error("Unpublish stopped at `{0}` step (completed: {1}): {2}")]
+    UnpublishFailed(String, String, Box<Error>),
+    #[error("Deploy is locked by {0}. Run `mashinka deploy unlock` if the lock is stale")]
+    DeployLocked(String),
+    #[error("Release {0} was deployed to container `{1}`, not `{2}`")]
+    ReleaseContainer(String, String, String),
+    // posts
+    #[error("Post with slug `{0}` not found")]
+    PostNotFound(String),
+    #[error("Slug `{0}` is used by posts in several languages ({1}). Set --lang")]
+    AmbiguousSlug(String, String),
+    #[error("{0} already exists")]
+    AlreadyExists(String),
+    #[error("Editor `{0}` failed: {1}")]
+    EditorFailed(String, String),
 }
 
 impl PartialEq for Error {
@@ -89,19 +175,52 @@ impl Details {
     fn push(&mut self, id: String, message: String) {
         self.items.push(Detail { id, message })
     }
+
+    /// Добавляет детали другой команды с отступом, например детали шагов `release`.
+    fn append_indented(&mut self, other: Details) {
+        for Detail { id, message } in other.items {
+            let id = if id.is_empty() { id } else { format!("  {id}") };
+            self.items.push(Detail { id, message });
+        }
+    }
 }
 
 impl Display for Details {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.items.is_empty() { return write!(f, "") }
 
-        let msg: String = self.items.iter().map(|d| {
+        let msg: Vec<String> = self.items.iter().map(|d| {
             if d.id.is_empty() { d.message.to_string() }
             else { format!("{}:{}", d.id, d.message) }
         }).collect();
 
-        write!(f, "{}", msg)
+        write!(f, "{}", msg.join("\n"))
+    }
+}
+
+/// Формат вывода команд, которые умеют выводить отчет (`--format=text|json`).
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Экранирует строку для вставки в json.
+pub fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
     }
+    escaped
 }
 
 /// Результат выполнения команды
@@ -109,34 +228,87 @@ impl Display for Details {
 pub struct CommandResult {
     command: String,
     details: Details,
+    /// Команда выполнена, но с ошибками, которые перечислены в `details`.
+    failed: bool,
 }
 
 impl CommandResult {
     pub fn summarize(&mut self) -> String {
         if self.command.is_empty() { return format!("{}", self.details) }
+        if self.failed { return format!("Command `{}` failed. {}", self.command, self.details) }
         format!("Command `{}` successfully completed. {}", self.command, self.details)
     }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
 }
 
 /// # Errors
 ///
 /// Вернет Error при выполнении команды и парсинге конфигурации.
-pub fn run(mut args: Args) -> Result<CommandResult, Error> {
+pub fn run(args: Args) -> Result<CommandResult, Error> {
+    let mut args = args.peekable();
     let command = match args.next() {
         Some(v) => v,
         None => String::from(HELP_COMMAND_NAME),
     };
 
-    let config = Config::from_args(args)?;
+    // `help deploy rollback` показывает справку по команде
+    if command == HELP_COMMAND_NAME {
+        let topic: Vec<String> = args.collect();
+        if topic.is_empty() {
+            return Help::new().run();
+        }
+        return Help::for_command(find_spec(&topic.join(" "))?).run();
+    }
+
+    // подкоманда, например `deploy rollback`, идет сразу после команды и не начинается с `--`
+    let has_subcommands = COMMANDS.iter().any(|spec| spec.name.starts_with(&format!("{command} ")));
+    let subcommand = args.next_if(|arg| has_subcommands && !arg.starts_with(PARAMETER_PREFIX));
+    let name = match &subcommand {
+        Some(subcommand) => format!("{command} {subcommand}"),
+        None => command.clone(),
+    };
+
+    let spec = find_spec(&name)?;
+    let config = Config::from_args(spec, args)?;
 
-    let cmd: Box<dyn Command> = match command.as_str() {
-        INDEX_COMMAND_NAME => Index::new(config),
-        PUBLISH_COMMAND_NAME => Publish::new(config),
-        HELP_COMMAND_NAME => Help::new(),
-        VERSION_COMMAND_NAME => Version::new(),
-        DEPLOY_COMMAND_NAME => Deploy::new(config),
-        _unknown => Help::new(),
+    let cmd: Box<dyn Command> = match (command.as_str(), subcommand.as_deref()) {
+        (INDEX_COMMAND_NAME, None) => Index::new(config),
+        (PUBLISH_COMMAND_NAME, None) => Publish::new(config),
+        (VERSION_COMMAND_NAME, None) => Version::new(),
+        (DEPLOY_COMMAND_NAME, None) => Deploy::new(config),
+        (COMPRESS_COMMAND_NAME, None) => Compress::new(config),
+        (BUILD_COMMAND_NAME, None) => Build::new(config),
+        (RELEASE_COMMAND_NAME, None) => Release::new(config),
+        (WATCH_COMMAND_NAME, None) => Watch::new(config),
+        (SERVE_COMMAND_NAME, None) => Serve::new(config),
+        (DOCTOR_COMMAND_NAME, None) => Doctor::new(config),
+        (LIST_COMMAND_NAME, None) => List::new(config),
+        (STATS_COMMAND_NAME, None) => Stats::new(config),
+        (UNPUBLISH_COMMAND_NAME, None) => Unpublish::new(config),
+        (EDIT_COMMAND_NAME, None) => Edit::new(config),
+        (RENAME_COMMAND_NAME, None) => Rename::new(config),
+        (DEPLOY_COMMAND_NAME, Some(HISTORY_SUBCOMMAND_NAME)) => History::new(config),
+        (DEPLOY_COMMAND_NAME, Some(ROLLBACK_SUBCOMMAND_NAME)) => Rollback::new(config),
+        (DEPLOY_COMMAND_NAME, Some(UNLOCK_SUBCOMMAND_NAME)) => Unlock::new(config),
+        (CONFIG_COMMAND_NAME, Some(SHOW_SUBCOMMAND_NAME)) => Show::new(config),
+        (CONFIG_COMMAND_NAME, Some(CHECK_SUBCOMMAND_NAME)) => Check::new(config),
+        _unknown => return Err(Error::UnknownCommand(name, String::from("Run `mashinka help` to see available commands."))),
     };
 
     cmd.run()
 }
+
+/// Описание команды по имени с подсказкой похожей команды, если такой команды нет.
+fn find_spec(name: &str) -> Result<&'static CommandSpec, Error> {
+    find_command(name).ok_or_else(|| {
+        let names: Vec<&str> = COMMANDS.iter().map(|spec| spec.name).collect();
+        let hint = match suggest(name, &names) {
+            Some(similar) => format!("Did you mean `{similar}`?"),
+            None => String::from("Run `mashinka help` to see available commands."),
+        };
+        Error::UnknownCommand(name.to_string(), hint)
+    })
+}
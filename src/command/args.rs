@@ -0,0 +1,232 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use crate::command::{build, compress, config, deploy, doctor, edit, list, rename, stats, unpublish, index, publish, release, serve, watch, Error};
+use crate::config::{PARAMETER_KEY_VALUE_DELIMITER, PARAMETER_PREFIX};
+
+/// Значение флага.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagValue {
+    /// Флаг без значения, например `--dry-run`.
+    Switch,
+    /// Произвольная строка, в скобках подсказка для справки, например `<path>`.
+    Text(&'static str),
+    /// Целое неотрицательное число.
+    Number,
+    /// Одно из перечисленных значений.
+    Choice(&'static [&'static str]),
+}
+
+/// Описание флага команды для разбора аргументов и справки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flag {
+    pub name: &'static str,
+    pub value: FlagValue,
+    pub help: &'static str,
+}
+
+impl Flag {
+    /// Флаг со значением в том виде, как его надо писать, например `--retries=N`.
+    pub fn usage(&self) -> String {
+        match self.value {
+            FlagValue::Switch => self.name.to_string(),
+            FlagValue::Text(hint) => format!("{}={hint}", self.name),
+            FlagValue::Number => format!("{}=N", self.name),
+            FlagValue::Choice(values) => format!("{}={}", self.name, values.join("|")),
+        }
+    }
+}
+
+/// Описание команды: имя (вместе с подкомандой), назначение и группы флагов.
+/// Группы позволяют команде `release` принимать флаги всех своих шагов.
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub flags: &'static [&'static [Flag]],
+}
+
+impl CommandSpec {
+    /// Все флаги команды без повторов в порядке объявления. Команды с флагами принимают и общие флаги.
+    pub fn flags(&self) -> Vec<&'static Flag> {
+        let mut flags: Vec<&'static Flag> = Vec::new();
+        let global = if self.flags.is_empty() { &[] } else { GLOBAL_FLAGS };
+        for flag in self.flags.iter().flat_map(|group| group.iter()).chain(global) {
+            if !flags.iter().any(|f| f.name == flag.name) {
+                flags.push(flag);
+            }
+        }
+        flags
+    }
+
+    fn find_flag(&self, name: &str) -> Option<&'static Flag> {
+        self.flags().into_iter().find(|flag| flag.name == name)
+    }
+
+    /// Разбирает аргументы `--flag` и `--flag=value` команды и проверяет их по описанию.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::UnknownFlag` для флага, которого нет у команды, `Error::UnexpectedArgument` для аргумента
+    /// без `--` и `Error::IncorrectFormat` если значение флага не подходит.
+    pub fn parse(&self, args: impl Iterator<Item=String>) -> Result<HashMap<String, String>, Error> {
+        let mut args_map = HashMap::new();
+
+        for arg in args {
+            if !arg.starts_with(PARAMETER_PREFIX) || arg == PARAMETER_PREFIX {
+                return Err(Error::UnexpectedArgument(arg, self.name.to_string()));
+            }
+
+            let (name, value) = match arg.split_once(PARAMETER_KEY_VALUE_DELIMITER) {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+
+            let Some(flag) = self.find_flag(&name) else {
+                let names: Vec<&str> = self.flags().iter().map(|f| f.name).collect();
+                let hint = match suggest(&name, &names) {
+                    Some(similar) => format!("Did you mean `{similar}`?"),
+                    None => format!("Run `mashinka help {}` to see available flags.", self.name),
+                };
+                return Err(Error::UnknownFlag(name, self.name.to_string(), hint));
+            };
+
+            let value = match (flag.value, value) {
+                (FlagValue::Switch, None) => String::from("true"),
+                (FlagValue::Switch, Some(_)) => {
+                    return Err(Error::IncorrectFormat(format!("{name} doesn't take a value")));
+                }
+                (_, None) => {
+                    return Err(Error::IncorrectFormat(format!("{name} requires a value, e.g. {}", flag.usage())));
+                }
+                (FlagValue::Number, Some(value)) if value.trim().parse::<u64>().is_err() => {
+                    return Err(Error::IncorrectFormat(format!("{name} should be a non negative number, got `{value}`")));
+                }
+                (FlagValue::Choice(values), Some(value)) if !values.contains(&value.as_str()) => {
+                    return Err(Error::IncorrectFormat(format!("{name} should be one of {}, got `{value}`", values.join(", "))));
+                }
+                (_, Some(value)) => value,
+            };
+
+            args_map.insert(name, value);
+        }
+
+        Ok(args_map)
+    }
+}
+
+pub const PROFILE: Flag = Flag { name: "--profile", value: FlagValue::Text("<name>"), help: "profile from mashinka.toml or ~/.config/mashinka/config.toml (MASHINKA_PROFILE)." };
+/// Флаги, которые принимает любая команда с флагами.
+pub const GLOBAL_FLAGS: &[Flag] = &[PROFILE];
+
+pub const DRY_RUN: Flag = Flag { name: "--dry-run", value: FlagValue::Switch, help: "run without side effects, only show what would be done." };
+pub const DRAFT_PATH: Flag = Flag { name: "--draft-path", value: FlagValue::Text("<path>"), help: "draft file or drafts dir (ABS_POST_DRAFT_FILE)." };
+pub const POSTS_PATH: Flag = Flag { name: "--posts-path", value: FlagValue::Text("<path>"), help: "posts dir (ABS_POSTS_PATH)." };
+pub const TRANSLATIONS_PATH: Flag = Flag { name: "--translations-path", value: FlagValue::Text("<path>"), help: "translations dir (ABS_TRANSLATIONS_PATH)." };
+pub const BLOG_PATH: Flag = Flag { name: "--blog-path", value: FlagValue::Text("<path>"), help: "grow site dir (ABS_BASE_PATH_TO_BLOG)." };
+pub const BUILD_PATH: Flag = Flag { name: "--build-path", value: FlagValue::Text("<path>"), help: "built site dir (ABS_BUILD_PATH)." };
+pub const EXCLUDE: Flag = Flag { name: "--exclude", value: FlagValue::Text("<patterns>"), help: "comma separated gitignore-style patterns to skip (also read from .mashinkaignore)." };
+pub const INCLUDE: Flag = Flag { name: "--include", value: FlagValue::Text("<patterns>"), help: "comma separated patterns to keep even if excluded." };
+pub const BROTLI: Flag = Flag { name: "--brotli", value: FlagValue::Switch, help: "also write brotli copies." };
+pub const MIN_SIZE: Flag = Flag { name: "--min-size", value: FlagValue::Number, help: "skip files smaller than N bytes (default 1024)." };
+pub const MIME_TYPES: Flag = Flag { name: "--mime-types", value: FlagValue::Text("<ext=type;...>"), help: "Content-Type overrides by extension (DEPLOY_MIME_TYPES)." };
+pub const CACHE_CONTROL: Flag = Flag { name: "--cache-control", value: FlagValue::Text("<glob=value;...>"), help: "Cache-Control rules by glob (DEPLOY_CACHE_CONTROL)." };
+pub const PER_FILE: Flag = Flag { name: "--per-file", value: FlagValue::Switch, help: "upload files one by one, resume interrupted upload (DEPLOY_PER_FILE)." };
+pub const PRUNE_MAX_PERCENT: Flag = Flag { name: "--prune-max-percent", value: FlagValue::Number, help: "max share of remote objects to delete without --force (default 20)." };
+pub const FORCE: Flag = Flag { name: "--force", value: FlagValue::Switch, help: "skip safety checks (e.g. prune threshold)." };
+pub const RETRIES: Flag = Flag { name: "--retries", value: FlagValue::Number, help: "number of attempts for transient API errors (default 3)." };
+pub const RETRY_DELAY: Flag = Flag { name: "--retry-delay", value: FlagValue::Number, help: "delay before the first retry in ms, doubled on each retry (default 500)." };
+pub const HISTORY_PATH: Flag = Flag { name: "--history-path", value: FlagValue::Text("<path>"), help: "local release history dir (DEPLOY_HISTORY_PATH)." };
+pub const LOCK_TTL: Flag = Flag { name: "--lock-ttl", value: FlagValue::Number, help: "seconds after which a deploy lock is considered stale (default 1800)." };
+pub const LANG: Flag = Flag { name: "--lang", value: FlagValue::Text("<lang>"), help: "language of posts, one of BLOG_LANGUAGES." };
+pub const SLUG: Flag = Flag { name: "--slug", value: FlagValue::Text("<slug>"), help: "slug of the post." };
+pub const REDIRECTS_PATH: Flag = Flag { name: "--redirects-path", value: FlagValue::Text("<path>"), help: "redirects file (REDIRECTS_FILE, default redirects.txt in the site dir)." };
+pub const FORMAT: Flag = Flag { name: "--format", value: FlagValue::Choice(&["text", "json"]), help: "output format of reports (text by default)." };
+
+/// Все команды с подкомандами. Порядок определяет порядок в справке.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "publish", about: "uses draft file as a source of content and creates post and translation based on its lang.", flags: &[publish::FLAGS] },
+    CommandSpec { name: "unpublish", about: "removes (or archives) a post by --slug, drops its translation and rebuilds the index.", flags: &[unpublish::FLAGS, index::FLAGS] },
+    CommandSpec { name: "edit", about: "opens a published post by --slug in $EDITOR as a draft and writes it back with an updated date.", flags: &[edit::FLAGS, index::FLAGS] },
+    CommandSpec { name: "rename", about: "changes the slug of a post (--slug to --to), its translation msgid and records a redirect from the old url.", flags: &[rename::FLAGS, index::FLAGS] },
+    CommandSpec { name: "index", about: "builds data file for indexing system (elasticlunr) based on posts content.", flags: &[index::FLAGS] },
+    CommandSpec { name: "build", about: "runs the site generator (BUILD_COMMAND, grow build by default) in ABS_BASE_PATH_TO_BLOG.", flags: &[build::FLAGS] },
+    CommandSpec { name: "release", about: "runs publish, index, build and deploy in sequence.", flags: &[release::FLAGS, publish::FLAGS, index::FLAGS, build::FLAGS, deploy::FLAGS] },
+    CommandSpec { name: "watch", about: "validates the draft (or every .md file in the drafts dir) on each save.", flags: &[watch::FLAGS] },
+    CommandSpec { name: "serve", about: "serves the build dir on localhost and the draft preview at /__draft with auto-reload.", flags: &[serve::FLAGS] },
+    CommandSpec { name: "list", about: "lists posts as a table or json with filters by lang, dates, keyword and author, sorting and paging.", flags: &[list::FLAGS] },
+    CommandSpec { name: "stats", about: "shows posts per year and month, words, reading time, top keywords, untranslated posts and longest gaps.", flags: &[stats::FLAGS] },
+    CommandSpec { name: "doctor", about: "checks all posts and reports every problem at once, --fix renames misplaced post files.", flags: &[doctor::FLAGS] },
+    CommandSpec { name: "deploy", about: "uploads and extract data to cloud storage (selectel for now).", flags: &[deploy::FLAGS] },
+    CommandSpec { name: "deploy history", about: "lists locally stored releases.", flags: &[deploy::history::FLAGS] },
+    CommandSpec { name: "deploy rollback", about: "re-uploads a stored release, previous one by default.", flags: &[deploy::rollback::FLAGS] },
+    CommandSpec { name: "deploy unlock", about: "removes a stale deploy lock left by an interrupted deploy.", flags: &[deploy::unlock::FLAGS] },
+    CommandSpec { name: "config show", about: "shows every resolved setting and its source (flag, env, file, default), secrets are masked.", flags: config::FLAGS },
+    CommandSpec { name: "config check", about: "checks that configured paths exist and are writable and deploy vars are complete.", flags: config::FLAGS },
+    CommandSpec { name: "compress", about: "writes .gz (and .br with --brotli) copies of html/css/js/json/svg build files.", flags: &[compress::FLAGS] },
+    CommandSpec { name: "help", about: "shows this help or usage of the given command, e.g. `help deploy rollback`.", flags: &[] },
+    CommandSpec { name: "version", about: "shows mashinka version.", flags: &[] },
+];
+
+/// Описание команды по имени вместе с подкомандой, например `deploy rollback`.
+pub fn find_command(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+/// Возвращает самый похожий на `input` вариант из `candidates`, если он отличается не больше чем на треть.
+pub fn suggest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    candidates.iter()
+        .map(|candidate| (edit_distance(input, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Расстояние Левенштейна между строками.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::args::{find_command, suggest, edit_distance};
+    use crate::command::Error;
+
+    #[test]
+    fn test_suggest_similar_flag() {
+        assert_eq!(1, edit_distance("--dry_run", "--dry-run"));
+        assert_eq!(Some("--dry-run"), suggest("--dry_run", &["--draft-path", "--dry-run"]));
+        assert_eq!(Some("--posts-path"), suggest("--post-path", &["--posts-path", "--draft-path"]));
+        assert_eq!(None, suggest("--verbose", &["--dry-run", "--posts-path"]));
+    }
+
+    #[test]
+    fn test_parse_validates_flags() {
+        let spec = find_command("deploy").unwrap();
+        let parse = |args: &[&str]| spec.parse(args.iter().map(ToString::to_string));
+
+        let args = parse(&["--dry-run", "--retries=5", "--format=json"]).unwrap();
+        assert_eq!(Some(&"true".to_string()), args.get("--dry-run"));
+        assert_eq!(Some(&"5".to_string()), args.get("--retries"));
+
+        assert!(matches!(parse(&["--dry_run"]), Err(Error::UnknownFlag(_, _, hint)) if hint.contains("--dry-run")));
+        assert!(matches!(parse(&["build"]), Err(Error::UnexpectedArgument(..))));
+        assert!(parse(&["--retries=many"]).is_err());
+        assert!(parse(&["--format=xml"]).is_err());
+        assert!(parse(&["--retries"]).is_err());
+        assert!(parse(&["--dry-run=yes"]).is_err());
+    }
+}
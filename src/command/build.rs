@@ -0,0 +1,122 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, SystemTime};
+use crate::command::{BUILD_COMMAND_NAME, Command, CommandResult, Details, Error};
+use crate::config::Config;
+use crate::grow::redirects::{Redirects, RedirectsFormat, NGINX_RULES_EXTENSION};
+use crate::command::args::{Flag, FlagValue, DRY_RUN, BLOG_PATH, BUILD_PATH, REDIRECTS_PATH};
+
+/// Флаги команды `build`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    BUILD_PATH,
+    BLOG_PATH,
+    Flag { name: "--build-command", value: FlagValue::Text("<cmd>"), help: "command to build the site (BUILD_COMMAND, default `grow build`)." },
+    REDIRECTS_PATH,
+    Flag { name: "--redirects", value: FlagValue::Choice(&["html", "nginx"]), help: "emit redirects of renamed posts as meta refresh pages in the build dir (html, default) or nginx rules next to the redirects file (REDIRECTS_FORMAT)." },
+];
+
+pub struct Build {
+    config: Config
+}
+
+impl Build {
+    pub fn new(config: Config) -> Box<Build> {
+        Box::new(Self { config })
+    }
+}
+
+/// Собирает сайт командой `BUILD_COMMAND` (по умолчанию `grow build`) в каталоге `ABS_BASE_PATH_TO_BLOG`.
+/// Вывод сборщика идет напрямую в консоль. После сборки проверяет, что в `ABS_BUILD_PATH` появились
+/// свежие файлы, иначе выгружать нечего: сборщик мог писать в другой каталог. Затем выводит перенаправления
+/// переименованных записей из файла перенаправлений, см. `Redirects`.
+impl Command for Build {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let blog_path = config.get_blog_path_or_default()?;
+        let build_path = config.get_build_path_or_default()?;
+        let build_command = config.get_build_command();
+
+        let mut details = Details::new();
+        details.push(String::from("run"), format!("`{build_command}` in {}", blog_path.display()));
+
+        if !config.is_dry_run() {
+            run_builder(&build_command, &blog_path, &build_path)?;
+        }
+
+        let redirects_path = config.get_redirects_path()?;
+        let redirects = Redirects::load(&redirects_path)?;
+        if !redirects.is_empty() {
+            let target = match config.get_redirects_format()? {
+                RedirectsFormat::Html => {
+                    if !config.is_dry_run() {
+                        redirects.write_html(&build_path)?;
+                    }
+                    format!("pages in {}", build_path.display())
+                }
+                RedirectsFormat::Nginx => {
+                    let rules_path = redirects_path.with_extension(NGINX_RULES_EXTENSION);
+                    if !config.is_dry_run() {
+                        fs::write(&rules_path, redirects.to_nginx()).map_err(Error::WriteFile)?;
+                    }
+                    format!("nginx rules in {}", rules_path.display())
+                }
+            };
+            details.push(String::from("redirects"), format!("{} as {target}", redirects.redirects.len()));
+        }
+
+        let command = String::from(BUILD_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
+
+/// Запускает `build_command` через `sh -c` в каталоге `blog_path` и проверяет, что `build_path` обновился.
+///
+/// # Errors
+///
+/// Вернет `Error::BuildFailed` если команда завершилась с ошибкой и `Error::BuildNotRefreshed`
+/// если в `build_path` нет файлов новее момента запуска.
+pub fn run_builder(build_command: &str, blog_path: &Path, build_path: &Path) -> Result<(), Error> {
+    // время изменения файлов может храниться с точностью до секунды
+    let started_at = SystemTime::now() - Duration::from_secs(1);
+
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(build_command)
+        .current_dir(blog_path)
+        .status()
+        .map_err(|e| Error::BuildFailed(build_command.to_string(), e.to_string()))?;
+
+    if !status.success() {
+        return Err(Error::BuildFailed(build_command.to_string(), status.to_string()));
+    }
+
+    match newest_modified(build_path) {
+        Some(modified) if modified >= started_at => Ok(()),
+        _ => Err(Error::BuildNotRefreshed(build_path.display().to_string())),
+    }
+}
+
+/// Время изменения самого нового файла в каталоге `dir` (рекурсивно).
+fn newest_modified(dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).ok()?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            newest = newest.max(modified);
+        }
+    }
+
+    newest
+}
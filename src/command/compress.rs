@@ -0,0 +1,50 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, COMPRESS_COMMAND_NAME, Details, Error};
+use crate::config::Config;
+use crate::deploy::archive::collect_files;
+use crate::deploy::compress::compress_files;
+use crate::deploy::filter::BuildFilter;
+use crate::command::args::{Flag, DRY_RUN, BUILD_PATH, EXCLUDE, INCLUDE, BROTLI, MIN_SIZE};
+
+/// Флаги команды `compress`.
+pub const FLAGS: &[Flag] = &[DRY_RUN, BUILD_PATH, EXCLUDE, INCLUDE, BROTLI, MIN_SIZE];
+
+pub struct Compress {
+    config: Config
+}
+
+impl Compress {
+    pub fn new(config: Config) -> Box<Compress> {
+        Box::new(Self { config })
+    }
+}
+
+/// Создает сжатые копии `.gz` (и `.br` с `--brotli`) файлов сборки, чтобы CDN отдавал их вместо
+/// оригиналов. Файлы меньше `COMPRESS_MIN_SIZE` и исключенные из выгрузки пропускаются.
+impl Command for Compress {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let build_path = config.get_build_path_or_default()?;
+
+        let filter = BuildFilter::new(&build_path, &config.get_exclude_patterns(), &config.get_include_patterns())?;
+        let files = collect_files(&build_path, &filter)?;
+
+        let summary = compress_files(
+            &build_path, &files, config.get_compress_min_size()?, config.is_brotli_enabled(), config.is_dry_run()
+        )?;
+
+        let mut details = Details::new();
+        details.push(String::from("compressed"), summary.written.len().to_string());
+        details.push(String::from("unchanged"), summary.skipped.to_string());
+
+        if config.is_dry_run() {
+            for path in summary.written {
+                details.push(String::new(), format!("  {}", path.display()));
+            }
+        }
+
+        let command = String::from(COMPRESS_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
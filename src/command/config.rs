@@ -0,0 +1,9 @@
+use crate::command::{build, compress, deploy, index, publish, serve};
+use crate::command::args::Flag;
+
+pub mod check;
+pub mod show;
+
+/// Флаги команд `config show` и `config check`: флаги всех команд, которые меняют настройки,
+/// чтобы увидеть и проверить настройки так, как их получит команда с теми же флагами.
+pub const FLAGS: &[&[Flag]] = &[publish::FLAGS, index::FLAGS, build::FLAGS, deploy::FLAGS, compress::FLAGS, serve::FLAGS];
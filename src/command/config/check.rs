@@ -0,0 +1,98 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use crate::command::{Command, CommandResult, CONFIG_CHECK_COMMAND_NAME, Details, Error};
+use crate::config::Config;
+use crate::config::settings::{settings, SettingKind, Source};
+
+/// Файл, которым проверяется запись в каталог. Создается и сразу удаляется.
+const WRITE_CHECK_FILE_NAME: &str = ".mashinka-write-check";
+
+pub struct Check {
+    config: Config
+}
+
+impl Check {
+    pub fn new(config: Config) -> Box<Check> {
+        Box::new(Self { config })
+    }
+}
+
+/// Проверяет, что заданные пути существуют и в них можно писать, числовые настройки являются числами,
+/// а реквизитов выгрузки хватает для деплоя. Незаданные пути пропускаются, каталоги по умолчанию
+/// (например история выгрузок) создаются при первой выгрузке.
+impl Command for Check {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let mut details = Details::new();
+        let mut failed = false;
+
+        for setting in settings() {
+            let Some((value, source)) = self.config.resolve(&setting) else {
+                if setting.is_path() {
+                    details.push(setting.env.to_string(), String::from(" not set, skipped"));
+                }
+                continue;
+            };
+
+            let result = match setting.kind {
+                SettingKind::Number => value.trim().parse::<u64>()
+                    .map(|_| String::from("ok"))
+                    .map_err(|_| format!("`{value}` is not a non negative number")),
+                _ if setting.is_path() && source == Source::Default && !Path::new(&value).exists() => {
+                    Ok(format!("{value} will be created"))
+                }
+                kind if setting.is_path() => check_path(Path::new(&value), kind).map(|()| format!("{value} ok")),
+                _ => continue,
+            };
+
+            let message = match result {
+                Ok(message) => format!(" {message}"),
+                Err(message) => {
+                    failed = true;
+                    format!(" {message}")
+                }
+            };
+            details.push(setting.env.to_string(), message);
+        }
+
+        let deploy = match self.config.get_deploy_config() {
+            Ok(_) => String::from(" ok"),
+            Err(e) => {
+                failed = true;
+                format!(" {e}")
+            }
+        };
+        details.push(String::from("deploy"), deploy);
+
+        let command = String::from(CONFIG_CHECK_COMMAND_NAME);
+        Ok(CommandResult { command, details, failed })
+    }
+}
+
+/// Проверяет путь `path` в зависимости от вида настройки, в ошибке описание проблемы.
+fn check_path(path: &Path, kind: SettingKind) -> Result<(), String> {
+    let display = path.display();
+    match kind {
+        SettingKind::Dir | SettingKind::WritableDir if !path.is_dir() => Err(format!("{display} is not a dir")),
+        SettingKind::WritableDir => check_writable_dir(path),
+        SettingKind::WritableFile if path.is_dir() => Err(format!("{display} is a dir, expected a file")),
+        SettingKind::WritableFile if path.exists() => OpenOptions::new().append(true).open(path)
+            .map(|_| ())
+            .map_err(|e| format!("{display} is not writable: {e}")),
+        SettingKind::WritableFile => match path.parent().filter(|parent| parent.is_dir()) {
+            Some(parent) => check_writable_dir(parent),
+            None => Err(format!("dir of {display} does not exist")),
+        },
+        _ if !path.exists() => Err(format!("{display} does not exist")),
+        _ => Ok(()),
+    }
+}
+
+/// Пробует создать и удалить файл в каталоге `dir`.
+fn check_writable_dir(dir: &Path) -> Result<(), String> {
+    let file = dir.join(WRITE_CHECK_FILE_NAME);
+    OpenOptions::new().write(true).create_new(true).open(&file)
+        .and_then(|_| fs::remove_file(&file))
+        .map_err(|e| format!("{} is not writable: {e}", dir.display()))
+}
@@ -0,0 +1,36 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, CONFIG_SHOW_COMMAND_NAME, Details, Error};
+use crate::config::Config;
+use crate::config::settings::{settings, Source, SECRET_MASK};
+
+pub struct Show {
+    config: Config
+}
+
+impl Show {
+    pub fn new(config: Config) -> Box<Show> {
+        Box::new(Self { config })
+    }
+}
+
+/// Выводит значение каждой настройки и откуда оно взято. Секреты маскируются, команды `*_COMMAND`
+/// не выполняются и не выводятся.
+impl Command for Show {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let mut details = Details::new();
+
+        for setting in settings() {
+            let message = match self.config.resolve(&setting) {
+                Some((value, source @ (Source::Command(_) | Source::SecretsFile(_)))) => format!(" {value} ({source})"),
+                Some((_, source)) if setting.is_secret() => format!(" {SECRET_MASK} ({source})"),
+                Some((value, source)) => format!(" {value} ({source})"),
+                None => String::from(" not set"),
+            };
+            details.push(setting.env.to_string(), message);
+        }
+
+        let command = String::from(CONFIG_SHOW_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
@@ -1,13 +1,58 @@
 #![allow(clippy::must_use_candidate)]
 #![allow(clippy::or_fun_call)]
 
-use std::{env, fs};
-use std::fs::File;
+use std::collections::HashSet;
+use std::env;
 use std::path::{Path, PathBuf};
-use flate2::Compression;
-use flate2::write::GzEncoder;
-use crate::command::{Command, CommandResult, DEPLOY_COMMAND_NAME, Details, Error};
-use crate::config::{Config, DeployConfig};
+use crate::command::{Command, CommandResult, DEPLOY_COMMAND_NAME, Details, Error, OutputFormat};
+use crate::config::{cache_dir, Config};
+use crate::deploy::{ARCHIVE_FILE_NAME, INDEX_FILE_ALIAS, NEWEST_POST_ALIAS, UPLOAD_JOURNALS_DIR_NAME};
+use crate::deploy::archive::{collect_files, encode_dir, object_key};
+use crate::deploy::compress::compress_files;
+use crate::deploy::filter::BuildFilter;
+use crate::deploy::history::ReleaseHistory;
+use crate::deploy::lock::with_lock;
+use crate::deploy::manifest::Manifest;
+use crate::deploy::metadata::MetadataRules;
+use crate::deploy::prune::{check_threshold, find_orphans};
+use crate::deploy::report::DeployReport;
+use crate::deploy::selectel::Selectel;
+use crate::deploy::upload::upload_files;
+use crate::deploy::verify::{index_url, verify_path};
+use crate::grow::post::GrowPost;
+use crate::command::args::{Flag, FlagValue, DRY_RUN, BUILD_PATH, POSTS_PATH, EXCLUDE, INCLUDE, BROTLI, MIN_SIZE, MIME_TYPES, RETRIES, RETRY_DELAY, HISTORY_PATH, LOCK_TTL, FORMAT, PER_FILE, CACHE_CONTROL, PRUNE_MAX_PERCENT, FORCE};
+
+pub mod history;
+pub mod rollback;
+pub mod unlock;
+
+/// Флаги команды `deploy`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    BUILD_PATH,
+    POSTS_PATH,
+    RETRIES,
+    RETRY_DELAY,
+    PER_FILE,
+    Flag { name: "--site-url", value: FlagValue::Text("<url>"), help: "site address for post-deploy verification (DEPLOY_SITE_URL)." },
+    Flag { name: "--verify-paths", value: FlagValue::Text("/,@newest,@index"), help: "comma separated paths to compare with build after upload (@newest - newest post, @index - ABS_INDEX_FILE)." },
+    Flag { name: "--no-verify", value: FlagValue::Switch, help: "skip post-deploy verification." },
+    HISTORY_PATH,
+    Flag { name: "--history-size", value: FlagValue::Number, help: "number of releases to keep in history (default 10)." },
+    Flag { name: "--manifest", value: FlagValue::Text("<path>"), help: "with --dry-run compare files with the given manifest instead of the current release." },
+    FORMAT,
+    EXCLUDE,
+    INCLUDE,
+    MIME_TYPES,
+    CACHE_CONTROL,
+    Flag { name: "--compress", value: FlagValue::Switch, help: "run compress before upload (DEPLOY_COMPRESS)." },
+    BROTLI,
+    MIN_SIZE,
+    Flag { name: "--prune", value: FlagValue::Switch, help: "delete remote objects missing in the build (with --dry-run lists them)." },
+    PRUNE_MAX_PERCENT,
+    FORCE,
+    LOCK_TTL,
+];
 
 pub struct Deploy {
     config: Config
@@ -19,47 +64,6 @@ impl Deploy {
     }
 }
 
-/// Создает tar.gz указанной директории.
-fn encode_dir(archive_path: &PathBuf, dir_path: &Path) -> Result<(), Error> {
-    let tar_gz = File::create(archive_path).map_err(Error::CreateFile)?;
-    let enc = GzEncoder::new(tar_gz, Compression::best());
-    let mut tar = tar::Builder::new(enc);
-    tar.append_dir_all(".", dir_path).map_err(Error::CreateArchive)?;
-    tar.finish().map_err(Error::CreateArchive)?;
-    Ok(())
-}
-
-/// Получает токен для работы с API Selectel.
-fn fetch_token(deploy_config: &DeployConfig) -> Result<String, Error> {
-    let response = ureq::get("https://api.selcdn.ru/auth/v1.0")
-        .set("X-Auth-User", deploy_config.username.as_str())
-        .set("X-Auth-Key", deploy_config.password.as_str())
-        .call()
-        .map_err(|e| Error::DeployApi(e.to_string()))?;
-
-    let token = response.header("X-Storage-Token")
-        .ok_or(Error::DeployApi("Key X-Storage-Token does not exists".to_string()))?;
-
-    Ok(String::from(token))
-}
-
-/// Выгружает архив в хранилище и распаковывает его в корень.
-fn upload_and_extract(archive: &PathBuf, deploy_config: &DeployConfig, token: &str) -> Result<(), Error> {
-    let account_id = deploy_config.account_id.as_str();
-    let container_id = deploy_config.container_id.as_str();
-
-    let endpoint = format!("https://api.selcdn.ru/v1/SEL_{account_id}/{container_id}/");
-    let content = fs::read(archive).map_err(Error::ReadFile)?;
-
-    ureq::put(endpoint.as_str())
-        .set("X-Auth-Token", token)
-        .query("extract-archive", "tar.gz")
-        .send_bytes(content.as_slice())
-        .map_err(|e| Error::DeployApi(e.to_string()))?;
-
-    Ok(())
-}
-
 /// Выгружает данные в облачное хранилище.
 /// Архивировать build в tar.gz;
 /// Получить токен от облачного хранилища;
@@ -67,27 +71,263 @@ fn upload_and_extract(archive: &PathBuf, deploy_config: &DeployConfig, token: &s
 /// curl -i -XPUT  https://api.selcdn.ru/v1/SEL_*****/new_container/archive.tar.gz/?extract-archive=tar.gz \
 /// -H "X-Auth-Token: $token" -T "archive.tar.gz"
 /// Отправить запрос на главную и вывести на экран или в открыть в браузере.
+///
+/// После выгрузки запрашивает адреса из `DEPLOY_VERIFY_PATHS` и сравнивает их с файлами сборки.
+/// При расхождении команда завершается с ненулевым кодом. Проверку можно отключить `--no-verify`.
+///
+/// С `--dry-run` ничего не выгружает, а выводит отчет: количество и размер файлов, размер архива,
+/// самые большие файлы и изменения относительно предыдущей выгрузки (`--format=json` для json).
+///
+/// С `--compress` перед выгрузкой создаются сжатые копии файлов (см. команду `compress`).
+///
+/// Файлы, подходящие под правила `DEPLOY_EXCLUDE` и `.mashinkaignore`, не попадают ни в архив,
+/// ни в выгрузку по файлам.
+///
+/// С `--prune` удаляет из хранилища объекты, которых нет в локальной сборке. Если таких объектов
+/// больше `DEPLOY_PRUNE_MAX_PERCENT` процентов, выгрузка прерывается, пока не задан `--force`.
+/// С `--dry-run --prune` выводит список объектов на удаление.
+///
+/// Выгрузка выполняется под блокировкой (объект `.mashinka.lock` в хранилище), чтобы две
+/// одновременные выгрузки не перемешали сборки. Брошенную блокировку снимает `deploy unlock`.
+///
+/// Каждая выгруженная версия сохраняется в локальную историю (см. `deploy history` и `deploy rollback`).
+///
+/// С параметром `--per-file` вместо архива файлы выгружаются по одному, а прерванная выгрузка
+/// продолжается с последнего успешно выгруженного файла. Каждому объекту задаются `Content-Type`,
+/// `Cache-Control` и `Content-Encoding` (для `.gz`/`.br` копий). Распаковка архива в хранилище эти заголовки
+/// не задает, поэтому выгрузка по файлам включается автоматически, если они нужны: заданы `DEPLOY_MIME_TYPES`
+/// или `DEPLOY_CACHE_CONTROL` или есть сжатые копии, см. `MetadataRules::requires_per_file`. Иначе сборка
+/// выгружается архивом, а `Content-Type` определяет хранилище.
 impl Command for Deploy {
     fn run(&self) -> Result<CommandResult, Error> {
         let config = &self.config;
         let grow_build_path = &config.get_build_path_or_default()?;
 
-        let archive_path = env::temp_dir().join(Path::new("build.tar.gz"));
-        encode_dir(&archive_path, grow_build_path)?;
+        let filter = BuildFilter::new(grow_build_path, &config.get_exclude_patterns(), &config.get_include_patterns())?;
 
-        let mut details = Details::new();
-        details.push(String::from("deployed_to"), config.get_deploy_config()?.destination);
+        if config.is_compress_enabled() && !config.is_dry_run() {
+            let files = collect_files(grow_build_path, &filter)?;
+            compress_files(grow_build_path, &files, config.get_compress_min_size()?, config.is_brotli_enabled(), false)?;
+        }
+
+        let files = collect_files(grow_build_path, &filter)?;
+        let manifest = Manifest::build(grow_build_path, &files)?;
 
+        let archive_path = env::temp_dir().join(Path::new(ARCHIVE_FILE_NAME));
+        encode_dir(&archive_path, grow_build_path, &files)?;
+
+        let mut details = Details::new();
         let command = String::from(DEPLOY_COMMAND_NAME);
 
         if config.is_dry_run() {
-            return Ok(CommandResult { command, details })
+            // без реквизитов: команды `*_COMMAND` и файл секретов при --dry-run не используются
+            let destination = config.get_var("DEPLOY_DEST").unwrap_or(String::from("DEPLOY_DEST is not resolved in dry-run"));
+            details.push(String::from("deployed_to"), destination);
+            return dry_run_report(config, &manifest, &archive_path, command, details);
+        }
+
+        // реквизиты получаются один раз: команды `*_COMMAND` и расшифровка файла секретов не повторяются
+        let deploy_config = config.get_deploy_config()?;
+        details.push(String::from("deployed_to"), deploy_config.destination.clone());
+        let site_url = config.get_site_url(&deploy_config.destination);
+        let client = Selectel::connect(deploy_config, config.get_retry_policy()?)?;
+
+        with_lock(&client, config.get_lock_ttl()?, || {
+            upload(config, &client, grow_build_path, &files, &manifest, &archive_path, &mut details)
+        })?;
+
+        if config.is_verify_skipped() {
+            return Ok(CommandResult { command, details, ..CommandResult::default() })
+        }
+
+        let failed = !verify_site(config, &site_url, grow_build_path, &mut details)?;
+
+        Ok(CommandResult { command, details, failed })
+    }
+}
+
+/// Выгружает сборку: архивом или по файлам, сохраняет версию в историю и удаляет лишние объекты.
+fn upload(config: &Config, client: &Selectel, build_path: &Path, files: &[PathBuf], manifest: &Manifest, archive_path: &Path, details: &mut Details) -> Result<(), Error> {
+    // порог проверяется до выгрузки, чтобы не выгружать сборку, которую потом откажемся чистить
+    let orphans = if config.is_prune_enabled() { find_prune_candidates(config, client, manifest)? } else { vec![] };
+
+    upload_build(config, client, build_path, files, archive_path, details)?;
+
+    let history = ReleaseHistory::new(&config.get_history_path_or_default()?);
+    let release = history.add(archive_path, build_path, manifest, &client.container())?;
+    history.set_current(&release)?;
+    history.prune(config.get_history_size()?)?;
+    details.push(String::from("release"), release.id);
+
+    if config.is_prune_enabled() {
+        prune_orphans(client, &orphans, details)?;
+    }
+
+    Ok(())
+}
+
+/// Выгружает файлы `files` из `build_path` архивом `archive_path` или по файлам. По файлам выгрузка идет,
+/// если задан `--per-file` или объектам нужны заголовки, см. `MetadataRules::requires_per_file`.
+pub(crate) fn upload_build(config: &Config, client: &Selectel, build_path: &Path, files: &[PathBuf], archive_path: &Path, details: &mut Details) -> Result<(), Error> {
+    let rules = MetadataRules::new(&config.get_mime_types()?, &config.get_cache_control_rules()?)?;
+    // заголовки можно задать только при выгрузке по файлам
+    let keys: HashSet<String> = files.iter().map(|f| object_key(f)).collect();
+
+    if config.is_per_file_upload() || rules.requires_per_file(&keys) {
+        let journals_dir = cache_dir()?.join(UPLOAD_JOURNALS_DIR_NAME);
+        let summary = upload_files(client, build_path, files, &rules, &journals_dir)?;
+        details.push(String::from("uploaded"), summary.uploaded.to_string());
+        details.push(String::from("resumed"), summary.resumed.to_string());
+    } else {
+        client.upload_and_extract(archive_path)?;
+    }
+
+    Ok(())
+}
+
+/// Удаляет из хранилища объекты `orphans`.
+pub(crate) fn prune_orphans(client: &Selectel, orphans: &[String], details: &mut Details) -> Result<(), Error> {
+    for key in orphans {
+        client.delete_object(key)?;
+    }
+    details.push(String::from("pruned"), orphans.len().to_string());
+    Ok(())
+}
+
+/// Возвращает объекты хранилища, которых нет в локальной сборке.
+///
+/// # Errors
+///
+/// Вернет `Error::PruneLimit` если объектов на удаление больше порога `DEPLOY_PRUNE_MAX_PERCENT` и не задан `--force`.
+pub(crate) fn find_prune_candidates(config: &Config, client: &Selectel, manifest: &Manifest) -> Result<Vec<String>, Error> {
+    let remote = client.list_objects()?;
+    let orphans = find_orphans(&remote, manifest);
+    check_threshold(orphans.len(), remote.len(), config.get_prune_max_percent()?, config.is_force())?;
+    Ok(orphans)
+}
+
+/// Формирует отчет о том, что будет выгружено. Файлы сравниваются с манифестом из `--manifest`,
+/// а если он не задан, то с манифестом выгруженной сейчас версии из истории.
+fn dry_run_report(config: &Config, manifest: &Manifest, archive_path: &Path, command: String, mut details: Details) -> Result<CommandResult, Error> {
+    let previous = match config.get_manifest_path() {
+        Some(path) => Some(Manifest::load(&path)?),
+        None => match config.get_history_path_or_default() {
+            Ok(path) => ReleaseHistory::new(&path).current_manifest()?,
+            Err(_) => None,
+        },
+    };
+
+    let report = DeployReport {
+        manifest,
+        compressed_size: archive_path.metadata().map_err(Error::ReadFile)?.len(),
+        diff: previous.map(|previous| manifest.diff(&previous)),
+    };
+
+    if config.get_output_format()? == OutputFormat::Json {
+        let mut details = Details::new();
+        details.push(String::new(), report.to_json());
+        return Ok(CommandResult { details, ..CommandResult::default() });
+    }
+
+    details.push(String::from("archive"), archive_path.to_string_lossy().to_string());
+    for (id, message) in report.to_lines() {
+        details.push(id, message);
+    }
+
+    if config.is_prune_enabled() {
+        let client = Selectel::connect(config.get_deploy_config()?, config.get_retry_policy()?)?;
+        let orphans = find_prune_candidates(config, &client, manifest)?;
+        details.push(String::from("prune"), orphans.len().to_string());
+        for key in orphans {
+            details.push(String::new(), format!("  prune {key}"));
         }
+    }
 
-        let deploy_config  = config.get_deploy_config()?;
-        let token = fetch_token(&deploy_config)?;
-        upload_and_extract(&archive_path, &deploy_config, token.as_str())?;
+    Ok(CommandResult { command, details, ..CommandResult::default() })
+}
+
+/// Проверяет, что выгруженный сайт `site_url` совпадает с локальной сборкой: запрашивает адреса из
+/// `Config::get_verify_paths` и сравнивает ответы с файлами сборки. Результат каждой проверки
+/// добавляется в `details`. Возвращает `false`, если есть хотя бы одно расхождение.
+fn verify_site(config: &Config, site_url: &str, build_path: &Path, details: &mut Details) -> Result<bool, Error> {
+    let retry = config.get_retry_policy()?;
+    let mut verified = true;
+
+    for path in config.get_verify_paths() {
+        // выгрузка уже прошла, поэтому адрес, который не удалось определить, - это проваленная проверка, а не ошибка
+        let url_path = match path.as_str() {
+            NEWEST_POST_ALIAS => newest_post_url(config).map_err(|e| e.to_string()),
+            INDEX_FILE_ALIAS => index_file_url(config, build_path),
+            _ => Ok(path.clone()),
+        };
+        let url_path = match url_path {
+            Ok(url_path) => url_path,
+            Err(reason) => {
+                verified = false;
+                details.push(format!("verify {path}"), reason);
+                continue;
+            }
+        };
+
+        match verify_path(site_url, &url_path, build_path, &retry) {
+            Ok(()) => details.push(format!("verify {url_path}"), String::from("ok")),
+            Err(reason) => {
+                verified = false;
+                details.push(format!("verify {url_path}"), reason);
+            }
+        }
+    }
+
+    Ok(verified)
+}
+
+/// Адрес файла индекса `ABS_INDEX_FILE` на сайте или причина, по которой его нельзя проверить.
+fn index_file_url(config: &Config, build_path: &Path) -> Result<String, String> {
+    let index_path = config.get_index_file_path_or_default().map_err(|e| e.to_string())?;
+    let blog_path = config.get_blog_path_or_default().ok();
+
+    index_url(&index_path, build_path, blog_path.as_deref()).ok_or(format!(
+        "{} is neither in the build dir nor in the site source dir", index_path.display()
+    ))
+}
+
+/// Возвращает адрес самой новой записи среди всех языков.
+fn newest_post_url(config: &Config) -> Result<String, Error> {
+    let posts_path = config.get_posts_path_or_default()?;
+    let mut newest: Option<GrowPost> = None;
+
+    for lang in config.available_languages() {
+        for post in GrowPost::fetch_posts_by_lang(&posts_path, lang)? {
+            if newest.as_ref().is_none_or(|n| post.published_at > n.published_at) {
+                newest = Some(post);
+            }
+        }
+    }
+
+    let post = newest.ok_or(Error::EmptyValue(String::from("posts")))?;
+    Ok(format!("/{}/posts/{}/", post.lang.to_lowercase(), post.slug))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::Details;
+    use crate::command::args::COMMANDS;
+    use crate::command::deploy::verify_site;
+    use crate::config::Config;
+
+    // Выгрузка уже прошла: неопределимая самая новая запись - это проваленная проверка, а не ошибка.
+    #[test]
+    fn test_unresolved_newest_post_fails_verification() {
+        let build = assert_fs::TempDir::new().unwrap();
+        let spec = COMMANDS.iter().find(|spec| spec.name == "deploy").unwrap();
+        let args = [
+            format!("--posts-path={}", build.path().join("missing").display()),
+            String::from("--verify-paths=@newest"),
+        ];
+        let config = Config::from_args(spec, args.into_iter()).unwrap();
+        let mut details = Details::new();
 
-        Ok(CommandResult { command, details })
+        let verified = verify_site(&config, "http://127.0.0.1:9", build.path(), &mut details).unwrap();
+        assert!(!verified);
     }
 }
@@ -0,0 +1,40 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, DEPLOY_HISTORY_COMMAND_NAME, Details, Error};
+use crate::config::Config;
+use crate::deploy::history::ReleaseHistory;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+use crate::command::args::{Flag, HISTORY_PATH};
+
+/// Флаги команды `deploy history`.
+pub const FLAGS: &[Flag] = &[HISTORY_PATH];
+
+pub struct History {
+    config: Config
+}
+
+impl History {
+    pub fn new(config: Config) -> Box<History> {
+        Box::new(Self { config })
+    }
+}
+
+/// Выводит локальную историю выгрузок от старых к новым. Выгруженная сейчас версия помечена `current`.
+impl Command for History {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let history = ReleaseHistory::new(&self.config.get_history_path_or_default()?);
+        let current = history.current();
+
+        let mut details = Details::new();
+        for release in history.list()? {
+            let marker = if Some(&release.id) == current.as_ref() { " (current)" } else { "" };
+            details.push(release.id, format!(
+                " {} {} bytes from {}{marker}",
+                release.created_at.format(ISO8601_DATE_TIME_FORMAT), release.size, release.build_path
+            ));
+        }
+
+        let command = String::from(DEPLOY_HISTORY_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
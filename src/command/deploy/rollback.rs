@@ -0,0 +1,102 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::{env, fs};
+use crate::command::{Command, CommandResult, DEPLOY_ROLLBACK_COMMAND_NAME, Details, Error};
+use crate::command::deploy::{find_prune_candidates, prune_orphans, upload_build};
+use crate::config::Config;
+use crate::deploy::archive::decode_archive;
+use crate::deploy::history::{Release, ReleaseHistory};
+use crate::deploy::lock::with_lock;
+use crate::deploy::selectel::Selectel;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+use crate::command::args::{Flag, FlagValue, DRY_RUN, HISTORY_PATH, RETRIES, RETRY_DELAY, LOCK_TTL, PER_FILE, MIME_TYPES, CACHE_CONTROL, PRUNE_MAX_PERCENT, FORCE};
+
+/// Флаги команды `deploy rollback`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    Flag { name: "--to", value: FlagValue::Text("<id>"), help: "release id from `deploy history` (previous release by default)." },
+    HISTORY_PATH,
+    RETRIES,
+    RETRY_DELAY,
+    LOCK_TTL,
+    PER_FILE,
+    MIME_TYPES,
+    CACHE_CONTROL,
+    PRUNE_MAX_PERCENT,
+    FORCE,
+];
+
+pub struct Rollback {
+    config: Config
+}
+
+impl Rollback {
+    pub fn new(config: Config) -> Box<Rollback> {
+        Box::new(Self { config })
+    }
+}
+
+/// Повторно выгружает версию из локальной истории: указанную в `--to` или предыдущую перед текущей.
+/// Версия выгружается так же, как `deploy`: архивом или по файлам с заголовками. Объекты, которых нет
+/// в манифесте версии (появились в более поздних версиях), удаляются с тем же порогом, что и `deploy --prune`.
+/// У версий без манифеста лишние объекты не удаляются.
+impl Command for Rollback {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let history = ReleaseHistory::new(&config.get_history_path_or_default()?);
+
+        let release = match config.get_release_id() {
+            Some(id) => history.find(&id)?,
+            None => history.previous()?,
+        };
+
+        let mut details = Details::new();
+        details.push(String::from("rollback_to"), format!(
+            "{} ({})", release.id, release.created_at.format(ISO8601_DATE_TIME_FORMAT)
+        ));
+
+        let command = String::from(DEPLOY_ROLLBACK_COMMAND_NAME);
+
+        if config.is_dry_run() {
+            if let Some(container) = config.get_var("DEPLOY_CONTAINER") {
+                release.check_container(&container)?;
+            }
+            return Ok(CommandResult { command, details, ..CommandResult::default() })
+        }
+
+        let deploy_config = config.get_deploy_config()?;
+        release.check_container(&deploy_config.container_id)?;
+        let client = Selectel::connect(deploy_config, config.get_retry_policy()?)?;
+        with_lock(&client, config.get_lock_ttl()?, || {
+            rollback(config, &client, &history, &release, &mut details)
+        })?;
+
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
+
+/// Выгружает версию `release` и удаляет объекты, которых нет в ее манифесте.
+fn rollback(config: &Config, client: &Selectel, history: &ReleaseHistory, release: &Release, details: &mut Details) -> Result<(), Error> {
+    // порог проверяется до выгрузки, как в `deploy`
+    let orphans = match history.manifest(release)? {
+        Some(manifest) => Some(find_prune_candidates(config, client, &manifest)?),
+        None => None,
+    };
+
+    let archive_path = history.archive_path(release);
+    let dir = env::temp_dir().join(format!("mashinka-rollback-{}", release.id));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(Error::WriteFile)?;
+    }
+    let files = decode_archive(&archive_path, &dir)?;
+    let uploaded = upload_build(config, client, &dir, &files, &archive_path, details);
+    fs::remove_dir_all(&dir).map_err(Error::WriteFile)?;
+    uploaded?;
+
+    match orphans {
+        Some(orphans) => prune_orphans(client, &orphans, details)?,
+        None => details.push(String::from("pruned"), String::from("skipped, the release has no manifest")),
+    }
+
+    history.set_current(release)
+}
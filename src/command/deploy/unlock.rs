@@ -0,0 +1,48 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, DEPLOY_UNLOCK_COMMAND_NAME, Details, Error};
+use crate::config::Config;
+use crate::deploy::lock::{current_lock, LOCK_OBJECT_NAME};
+use crate::deploy::selectel::Selectel;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+use crate::command::args::{Flag, DRY_RUN, RETRIES, RETRY_DELAY};
+
+/// Флаги команды `deploy unlock`.
+pub const FLAGS: &[Flag] = &[DRY_RUN, RETRIES, RETRY_DELAY];
+
+pub struct Unlock {
+    config: Config
+}
+
+impl Unlock {
+    pub fn new(config: Config) -> Box<Unlock> {
+        Box::new(Self { config })
+    }
+}
+
+/// Снимает блокировку выгрузки, например если выгрузка была прервана и блокировка осталась.
+/// С `--dry-run` только показывает, кем и когда установлена блокировка.
+impl Command for Unlock {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let client = Selectel::connect(config.get_deploy_config()?, config.get_retry_policy()?)?;
+
+        let mut details = Details::new();
+        let command = String::from(DEPLOY_UNLOCK_COMMAND_NAME);
+
+        let Some(lock) = current_lock(&client)? else {
+            details.push(String::from("lock"), String::from("none"));
+            return Ok(CommandResult { command, details, ..CommandResult::default() });
+        };
+
+        details.push(String::from("lock"), format!(
+            "{}@{} since {} UTC", lock.owner, lock.host, lock.created_at.format(ISO8601_DATE_TIME_FORMAT)
+        ));
+
+        if !config.is_dry_run() {
+            client.delete_object(LOCK_OBJECT_NAME)?;
+        }
+
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
@@ -0,0 +1,197 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::command::{Command, CommandResult, Details, DOCTOR_COMMAND_NAME, Error};
+use crate::config::Config;
+use crate::grow::SOURCE_DIR_NAME;
+use crate::grow::post::{GrowPost, GrowPostTranslation};
+use crate::command::args::{Flag, FlagValue, BLOG_PATH, DRY_RUN, POSTS_PATH, TRANSLATIONS_PATH};
+
+/// Флаги команды `doctor`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    BLOG_PATH,
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    Flag { name: "--fix", value: FlagValue::Switch, help: "move misplaced and rename misnamed post files (with --dry-run only shows what would be renamed)." },
+];
+
+const FRONT_MATTER_PROBLEM: &str = "front_matter";
+const WRONG_LANG_DIR_PROBLEM: &str = "wrong_lang_dir";
+const FILE_NAME_PROBLEM: &str = "file_name";
+const DUPLICATE_SLUG_PROBLEM: &str = "duplicate_slug";
+const MISSING_IMAGE_PROBLEM: &str = "missing_image";
+const MISSING_TRANSLATION_PROBLEM: &str = "missing_translation";
+const POSTS_DIR_PROBLEM: &str = "posts_dir";
+
+/// Картинки в тексте записи: markdown `![alt](url)` и `<img src="url">`.
+static IMAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"!\[[^\]]*\]\(\s*([^)\s]+)|<img[^>]*\ssrc\s*=\s*["']([^"']+)["']"#).unwrap()
+});
+
+pub struct Doctor {
+    config: Config
+}
+
+impl Doctor {
+    pub fn new(config: Config) -> Box<Doctor> {
+        Box::new(Self { config })
+    }
+}
+
+/// Проблема, найденная в записи. Если задан `rename_to`, то проблема исправляется переименованием файла.
+struct Problem {
+    kind: &'static str,
+    path: PathBuf,
+    message: String,
+    rename_to: Option<PathBuf>,
+}
+
+impl Problem {
+    fn new(kind: &'static str, path: &Path, message: String) -> Self {
+        Self { kind, path: path.to_path_buf(), message, rename_to: None }
+    }
+}
+
+/// Проверяет все записи сайта и выводит все найденные проблемы разом: неразбираемые записи, записи не в
+/// каталоге своего языка, имена файлов не по `build_post_path`, повторяющиеся slug, отсутствующие картинки
+/// и переводы заголовков. С `--fix` переносит и переименовывает файлы записей, если файла с таким именем еще нет.
+impl Command for Doctor {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let posts_path = config.get_posts_path_or_default()?;
+        let blog_path = config.get_blog_path_or_default().ok();
+
+        let mut problems: Vec<Problem> = Vec::new();
+
+        // переводы и slug проверяются по языку самой записи, а не по каталогу, в котором она лежит
+        let mut translations: HashMap<String, (PathBuf, Option<HashSet<String>>)> = HashMap::new();
+        for lang in config.available_languages() {
+            let translation_path = config.get_translation_file_path(lang)?;
+            let ids = match GrowPostTranslation::fetch_translations(&translation_path) {
+                Ok(translations) => Some(translations.into_iter().map(|t| t.id).collect()),
+                Err(e) => {
+                    problems.push(Problem::new(MISSING_TRANSLATION_PROBLEM, &translation_path, e.to_string()));
+                    None
+                }
+            };
+            translations.insert(lang.to_lowercase(), (translation_path, ids));
+        }
+
+        let mut slugs: HashMap<(String, String), PathBuf> = HashMap::new();
+        for lang in config.available_languages() {
+            let posts = match GrowPost::scan_posts_by_lang(&posts_path, lang) {
+                Ok(posts) => posts,
+                Err(e) => {
+                    problems.push(Problem::new(POSTS_DIR_PROBLEM, &posts_path.join(lang.to_lowercase()), e.to_string()));
+                    continue;
+                }
+            };
+
+            for (path, post) in posts {
+                let post = match post {
+                    Ok(post) if post.slug.is_empty() => {
+                        problems.push(Problem::new(FRONT_MATTER_PROBLEM, &path, String::from("slugRu or slugEn is not set")));
+                        continue;
+                    }
+                    Ok(post) => post,
+                    Err(e) => {
+                        problems.push(Problem::new(FRONT_MATTER_PROBLEM, &path, e.to_string()));
+                        continue;
+                    }
+                };
+
+                let post_lang = post.lang.to_lowercase();
+                match slugs.get(&(post_lang.clone(), post.slug.clone())) {
+                    Some(first) => problems.push(Problem::new(DUPLICATE_SLUG_PROBLEM, &path, format!(
+                        "slug `{}` is already used by {}", post.slug, relative(first, &posts_path)
+                    ))),
+                    None => { slugs.insert((post_lang.clone(), post.slug.clone()), path.clone()); }
+                }
+
+                if let Some((translation_path, Some(ids))) = translations.get(&post_lang) {
+                    if !ids.contains(&post.slug) {
+                        problems.push(Problem::new(MISSING_TRANSLATION_PROBLEM, &path, format!(
+                            "no msgid `{}` in {}", post.slug, translation_path.display()
+                        )));
+                    }
+                }
+
+                if let Some(blog_path) = &blog_path {
+                    for image in image_urls(&post) {
+                        if !blog_path.join(SOURCE_DIR_NAME).join(image.trim_start_matches('/')).is_file() {
+                            problems.push(Problem::new(MISSING_IMAGE_PROBLEM, &path, format!("{image} not found")));
+                        }
+                    }
+                }
+
+                let expected = post.build_post_path(&posts_path);
+                if expected != path {
+                    let (kind, message) = if post.lang == lang {
+                        (FILE_NAME_PROBLEM, format!("expected name {}", relative(&expected, &posts_path)))
+                    } else {
+                        (WRONG_LANG_DIR_PROBLEM, format!("{} post in {} dir", post.lang.to_lowercase(), lang.to_lowercase()))
+                    };
+                    problems.push(Problem { rename_to: Some(expected), ..Problem::new(kind, &path, message) });
+                }
+            }
+        }
+
+        let mut details = Details::new();
+        if blog_path.is_none() {
+            details.push(String::from("images"), String::from(" skipped, ABS_BASE_PATH_TO_BLOG is not set"));
+        }
+
+        let mut fixed = 0;
+        for problem in &problems {
+            let mut message = format!(" {}: {}", relative(&problem.path, &posts_path), problem.message);
+            if let (true, Some(target)) = (config.is_fix(), &problem.rename_to) {
+                let target_name = relative(target, &posts_path);
+                if target.exists() {
+                    message.push_str(&format!(", not fixed: {target_name} already exists"));
+                } else if config.is_dry_run() {
+                    message.push_str(&format!(", would be renamed to {target_name}"));
+                } else {
+                    rename(&problem.path, target)?;
+                    message.push_str(&format!(", renamed to {target_name}"));
+                    fixed += 1;
+                }
+            }
+            details.push(problem.kind.to_string(), message);
+        }
+
+        details.push(String::from("problems"), format!("{}, fixed {fixed}", problems.len()));
+
+        let command = String::from(DOCTOR_COMMAND_NAME);
+        Ok(CommandResult { command, details, failed: problems.len() > fixed })
+    }
+}
+
+/// Адреса картинок записи от корня сайта: картинка записи и картинки из текста (markdown и `<img>`).
+/// Внешние адреса и относительные пути пропускаются.
+fn image_urls(post: &GrowPost) -> Vec<String> {
+    let from_text = IMAGE_RE.captures_iter(&post.text)
+        .filter_map(|cap| cap.get(1).or(cap.get(2)))
+        .map(|url| url.as_str().to_string());
+
+    std::iter::once(post.image.clone()).chain(from_text)
+        .map(|url| url.split(['?', '#']).next().unwrap_or_default().to_string())
+        .filter(|url| url.starts_with('/') && !url.starts_with("//"))
+        .collect()
+}
+
+/// Путь относительно каталога записей для вывода.
+fn relative(path: &Path, posts_path: &Path) -> String {
+    path.strip_prefix(posts_path).unwrap_or(path).display().to_string()
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), Error> {
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir).map_err(Error::WriteFile)?;
+    }
+    fs::rename(from, to).map_err(Error::WriteFile)
+}
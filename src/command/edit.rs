@@ -0,0 +1,151 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::{env, fs, process};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use crate::command::{Command, CommandResult, Details, EDIT_COMMAND_NAME, Error, INDEX_COMMAND_NAME};
+use crate::command::index::Index;
+use crate::config::Config;
+use crate::grow::catalog::find_post;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+use crate::grow::post::{DraftPost, GrowPost, GrowPostTranslation, WriterWrapper};
+use crate::grow::serdes::GrowDeserializer;
+use crate::command::args::{Flag, FlagValue, DRY_RUN, LANG, POSTS_PATH, SLUG, TRANSLATIONS_PATH};
+
+/// Флаги команды `edit`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    SLUG,
+    LANG,
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    Flag { name: "--editor", value: FlagValue::Text("<command>"), help: "editor command (VISUAL or EDITOR, default vi)." },
+];
+
+pub struct Edit {
+    config: Config
+}
+
+impl Edit {
+    pub fn new(config: Config) -> Box<Edit> {
+        Box::new(Self { config })
+    }
+}
+
+/// Исправляет опубликованную запись: превращает ее в черновик, открывает его в редакторе и записывает
+/// изменения в тот же файл. Дата публикации и slug не меняются, добавляется дата исправления `updated`.
+/// Если изменился заголовок, то он заменяется в `messages.po`. После исправления пересобирается индекс.
+impl Command for Edit {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let slug = config.get_slug()?;
+        let (post_path, post) = find_post(&config.get_posts_path_or_default()?, &config.get_post_languages()?, &slug)?;
+        let translation_path = config.get_translation_file_path(post.lang)?;
+
+        let title = GrowPostTranslation::fetch_translations(&translation_path)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|t| t.id == slug)
+            .map_or(slug.clone(), |t| t.translated_value);
+        let draft = post.to_draft(title);
+
+        let mut details = Details::new();
+        details.push(String::from("post_path"), post_path.display().to_string());
+
+        let draft_path = create_draft_file(&post, &draft.to_string())?;
+        let edited = edit_draft(&config.get_editor(), &draft_path)?;
+
+        if edited == draft {
+            fs::remove_file(&draft_path).map_err(Error::WriteFile)?;
+            details.push(String::from("changes"), String::from("no changes"));
+            let command = String::from(EDIT_COMMAND_NAME);
+            return Ok(CommandResult { command, details, ..CommandResult::default() });
+        }
+
+        // черновик удаляется только после записи, иначе отклоненные правки потеряются
+        if edited.lang != post.lang {
+            return Err(Error::IncorrectFormat(format!(
+                "lang of the post can't be changed from {} to {}. The edited draft is kept in {}",
+                post.lang.to_lowercase(), edited.lang.to_lowercase(), draft_path.display()
+            )));
+        }
+
+        let mut updated = post.clone();
+        updated.description = edited.description.clone();
+        updated.keywords = edited.keywords.clone();
+        updated.text = edited.text.clone();
+        updated.updated_at = Some(Utc::now());
+        details.push(String::from("updated"), updated.updated_at.unwrap_or_default().format(ISO8601_DATE_TIME_FORMAT).to_string());
+
+        let title_changed = edited.title != draft.title;
+        if title_changed {
+            details.push(String::from("title"), format!("{} -> {}", draft.title, edited.title));
+            details.push(String::from("translation_path"), translation_path.display().to_string());
+        }
+
+        if !config.is_dry_run() {
+            if title_changed {
+                if let Some(dir) = translation_path.parent() {
+                    fs::create_dir_all(dir).map_err(Error::WriteFile)?;
+                }
+            }
+            WriterWrapper::write_file(&post_path, &updated.to_string())?;
+
+            if title_changed {
+                let translation = GrowPostTranslation { id: slug.clone(), translated_value: edited.title };
+                let replaced = if translation_path.exists() {
+                    GrowPostTranslation::replace_translation(&translation_path, &slug, Some(&translation))?
+                } else {
+                    None
+                };
+                if replaced.is_none() {
+                    WriterWrapper::write_file_with_append(&translation_path, &translation.to_string())?;
+                }
+            }
+        }
+        fs::remove_file(&draft_path).map_err(Error::WriteFile)?;
+
+        let index = Index::new(config.clone()).run()?;
+        details.push(String::from(INDEX_COMMAND_NAME), String::from(if index.failed { "failed" } else { "done" }));
+        details.append_indented(index.details);
+
+        let command = String::from(EDIT_COMMAND_NAME);
+        Ok(CommandResult { command, details, failed: index.failed })
+    }
+}
+
+/// Создает новый файл черновика во временном каталоге. Имя с номером процесса и временем, а файл создается
+/// только если его еще нет, поэтому чужой файл или символическая ссылка с тем же именем не перезаписываются.
+fn create_draft_file(post: &GrowPost, content: &str) -> Result<PathBuf, Error> {
+    let suffix = format!("{}-{}", process::id(), Utc::now().timestamp_subsec_nanos());
+    let path = env::temp_dir().join(format!("mashinka-edit-{}@{}-{suffix}.md", post.slug, post.lang.to_lowercase()));
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(Error::WriteFile)?;
+    file.write_all(content.as_bytes()).map_err(Error::WriteFile)?;
+    Ok(path)
+}
+
+/// Открывает черновик `draft_path` в редакторе `editor` и разбирает результат. Редактор запускается через `sh`,
+/// поэтому может содержать аргументы: `code --wait`.
+fn edit_draft(editor: &str, draft_path: &Path) -> Result<DraftPost, Error> {
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(draft_path)
+        .status()
+        .map_err(|e| Error::EditorFailed(editor.to_string(), e.to_string()))?;
+
+    if !status.success() {
+        return Err(Error::EditorFailed(editor.to_string(), format!("{status}, draft is kept in {}", draft_path.display())));
+    }
+
+    let content = fs::read_to_string(draft_path).map_err(Error::ReadFile)?;
+    DraftPost::deserialize(&content).map_err(|e| {
+        Error::IncorrectFormat(format!("{e}. The edited draft is kept in {}", draft_path.display()))
+    })
+}
@@ -1,32 +1,66 @@
 #![allow(clippy::must_use_candidate)]
 
 use colored::Colorize;
+use crate::command::args::{COMMANDS, CommandSpec, DRY_RUN, FORMAT, PROFILE};
 use crate::command::{Command, CommandResult, Error};
 
-pub struct Help;
+/// Справка по всем командам или по одной команде.
+pub struct Help {
+    spec: Option<&'static CommandSpec>,
+}
 
 impl Help {
     pub fn new() -> Box<Help> {
-        Box::new(Self)
+        Box::new(Self { spec: None })
+    }
+
+    pub fn for_command(spec: &'static CommandSpec) -> Box<Help> {
+        Box::new(Self { spec: Some(spec) })
     }
 }
 
 impl Command for Help {
     fn run(&self) -> Result<CommandResult, Error> {
-        println!("{} {}", "Usage:".green().bold(), "mashinka CMD --arg-name --argv-name=value".blue());
-        println!("{} {}", "Example:".green().bold(), "mashinka publish --dry-run".blue());
-        println!();
-        println!("{}", "Available commands:".green().bold());
-        println!("{} - uses draft file as a source of content and create to post and translation
-based on specified lang.", "publish".blue());
-        println!("{} - builds data file for indexing system (elasticlunr) based on posts content.", "index".blue());
-        println!("{} - uploads and extract data to cloud storage (selectel for now).", "deploy".blue());
-        println!("{} - shows content from HELP.md file.", "help".blue());
-        println!("{} - shows mashinka version.", "version".blue());
-        println!();
-        println!("{}", "Miscellaneous:".green().bold());
-        println!("{} - runs command in so called dry-run mode( without side artifacts).", "--dry-run".blue());
+        match self.spec {
+            Some(spec) => print_command_help(spec),
+            None => print_help(),
+        }
 
         Ok(CommandResult::default())
     }
 }
+
+fn print_help() {
+    println!("{} {}", "Usage:".green().bold(), "mashinka CMD --arg-name --argv-name=value".blue());
+    println!("{} {}", "Example:".green().bold(), "mashinka publish --dry-run".blue());
+    println!();
+    println!("{}", "Available commands:".green().bold());
+    for spec in COMMANDS {
+        println!("{} - {}", spec.name.blue(), spec.about);
+    }
+    println!();
+    println!("{}", "Miscellaneous:".green().bold());
+    println!("{} - {}", DRY_RUN.usage().blue(), DRY_RUN.help);
+    println!("{} - {}", FORMAT.usage().blue(), FORMAT.help);
+    println!("{} - {}", PROFILE.usage().blue(), PROFILE.help);
+    println!("Run {} to see flags of the command.", "mashinka help <command>".blue());
+}
+
+fn print_command_help(spec: &CommandSpec) {
+    let flags = spec.flags();
+    let usage = if flags.is_empty() { format!("mashinka {}", spec.name) } else { format!("mashinka {} [flags]", spec.name) };
+
+    println!("{} {}", "Usage:".green().bold(), usage.blue());
+    println!();
+    println!("{}", spec.about);
+
+    if flags.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "Flags:".green().bold());
+    for flag in flags {
+        println!("{} - {}", flag.usage().blue(), flag.help);
+    }
+}
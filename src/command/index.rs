@@ -2,12 +2,22 @@
 #![allow(clippy::or_fun_call)]
 
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use regex::RegexBuilder;
 use crate::command::{Command, CommandResult, Details, Error, INDEX_COMMAND_NAME};
 use crate::config::Config;
 use crate::grow::post::{GrowPost, GrowPostTranslation, WriterWrapper};
 use crate::grow::serdes::{process_template};
 use crate::grow::TRANSLATION_INDEX_TEMPLATE;
+use crate::command::args::{Flag, FlagValue, DRY_RUN, POSTS_PATH, TRANSLATIONS_PATH};
+
+/// Флаги команды `index`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    Flag { name: "--index-path", value: FlagValue::Text("<path>"), help: "index data file (ABS_INDEX_FILE)." },
+];
 
 pub struct Index {
     config: Config
@@ -49,15 +59,15 @@ impl IndexContent {
     }
 }
 
-impl ToString for IndexContent {
-    fn to_string(&self) -> String {
-        process_template(
+impl Display for IndexContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", process_template(
             TRANSLATION_INDEX_TEMPLATE.to_string(), HashMap::from([
                 ("id", self.id.clone()),
                 ("title", self.title.clone()),
                 ("content", self.content.clone())
             ])
-        )
+        ))
     }
 }
 
@@ -70,9 +80,7 @@ impl Command for Index {
 
         // Собираем все переводы
         for lang in config.available_languages() {
-            let translation_path = config.get_translations_path_or_default()?
-                .join(lang.to_lowercase())
-                .join("LC_MESSAGES/messages.po");
+            let translation_path = config.get_translation_file_path(lang)?;
 
             let translations = GrowPostTranslation::fetch_translations(&translation_path)?;
             all_translations = [all_translations, translations].concat();
@@ -108,11 +116,11 @@ impl Command for Index {
         let command = String::from(INDEX_COMMAND_NAME);
 
         if config.is_dry_run() {
-            return Ok(CommandResult { command, details })
+            return Ok(CommandResult { command, details, ..CommandResult::default() })
         }
 
         WriterWrapper::write_file(index_path, &as_json_list)?;
 
-        Ok(CommandResult { command, details })
+        Ok(CommandResult { command, details, ..CommandResult::default() })
     }
 }
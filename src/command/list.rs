@@ -0,0 +1,92 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, Details, Error, escape_json, OutputFormat};
+use crate::config::Config;
+use crate::grow::catalog::{load_posts, page, sort_posts, CatalogPost};
+use crate::grow::{ISO8601_DATE_FORMAT, ISO8601_DATE_TIME_FORMAT};
+use crate::command::args::{Flag, FlagValue, FORMAT, LANG, POSTS_PATH, TRANSLATIONS_PATH};
+
+/// Флаги команды `list`.
+pub const FLAGS: &[Flag] = &[
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    LANG,
+    Flag { name: "--from", value: FlagValue::Text("<YYYY-MM-DD>"), help: "posts published on this date or later." },
+    Flag { name: "--to", value: FlagValue::Text("<YYYY-MM-DD>"), help: "posts published on this date or earlier." },
+    Flag { name: "--keyword", value: FlagValue::Text("<word>"), help: "posts with a keyword containing the word (case insensitive)." },
+    Flag { name: "--author", value: FlagValue::Text("<name>"), help: "posts with an author containing the name (case insensitive)." },
+    Flag { name: "--sort", value: FlagValue::Choice(&["date", "title"]), help: "sort newest first (date, default) or by title." },
+    Flag { name: "--reverse", value: FlagValue::Switch, help: "reverse sort order." },
+    Flag { name: "--page", value: FlagValue::Number, help: "page number starting from 1." },
+    Flag { name: "--per-page", value: FlagValue::Number, help: "posts per page (LIST_PER_PAGE, default 20)." },
+    FORMAT,
+];
+
+pub struct List {
+    config: Config
+}
+
+impl List {
+    pub fn new(config: Config) -> Box<List> {
+        Box::new(Self { config })
+    }
+}
+
+/// Выводит записи блога таблицей или в json с фильтрами по языку, датам, ключевому слову и автору,
+/// сортировкой по дате или заголовку и разбивкой на страницы.
+impl Command for List {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let filter = config.get_post_filter()?;
+        let (page_number, per_page) = config.get_page()?;
+
+        let mut posts: Vec<CatalogPost> = load_posts(
+            &config.get_posts_path_or_default()?,
+            &config.available_languages(),
+            &|lang| config.get_translation_file_path(lang),
+        )?.into_iter().filter(|p| filter.matches(&p.post)).collect();
+        sort_posts(&mut posts, config.get_sort_by()?, config.is_reverse());
+
+        let (items, pages) = page(&posts, page_number, per_page);
+
+        let mut details = Details::new();
+        match config.get_output_format()? {
+            OutputFormat::Json => details.push(String::new(), to_json(items, page_number, pages, posts.len())),
+            OutputFormat::Text => {
+                for line in to_table(items) {
+                    details.push(String::new(), line);
+                }
+                details.push(String::new(), format!("page {page_number} of {pages}, {} posts", posts.len()));
+            }
+        }
+
+        Ok(CommandResult { details, ..CommandResult::default() })
+    }
+}
+
+/// Таблица с колонками дата, язык, slug и заголовок, выровненными по самому длинному значению.
+fn to_table(posts: &[CatalogPost]) -> Vec<String> {
+    let slug_width = posts.iter().map(|p| p.post.slug.chars().count()).max().unwrap_or(0).max("SLUG".len());
+
+    let mut lines = vec![format!("{:<10}  {:<4}  {:<slug_width$}  TITLE", "DATE", "LANG", "SLUG")];
+    for CatalogPost { post, title } in posts {
+        lines.push(format!(
+            "{:<10}  {:<4}  {:<slug_width$}  {title}",
+            post.published_at.format(ISO8601_DATE_FORMAT), post.lang.to_lowercase(), post.slug
+        ));
+    }
+    lines
+}
+
+fn to_json(posts: &[CatalogPost], page: usize, pages: usize, total: usize) -> String {
+    let items: Vec<String> = posts.iter().map(|CatalogPost { post, title }| {
+        let keywords: Vec<String> = post.keywords.iter().map(|k| format!(r#""{}""#, escape_json(k))).collect();
+        format!(
+            r#"{{"slug": "{}", "lang": "{}", "title": "{}", "author": "{}", "published": "{}", "keywords": [{}]}}"#,
+            escape_json(&post.slug), post.lang.to_lowercase(), escape_json(title), escape_json(&post.author),
+            post.published_at.format(ISO8601_DATE_TIME_FORMAT), keywords.join(", ")
+        )
+    }).collect();
+
+    format!(r#"{{"page": {page}, "pages": {pages}, "total": {total}, "posts": [{}]}}"#, items.join(", "))
+}
@@ -3,6 +3,10 @@ use crate::command::{Command, CommandResult, Details, Error, PUBLISH_COMMAND_NAM
 use crate::config::Config;
 use crate::grow::post::{DraftPost, GrowPostTranslation, WriterWrapper};
 use crate::grow::serdes::GrowDeserializer;
+use crate::command::args::{Flag, DRY_RUN, DRAFT_PATH, POSTS_PATH, TRANSLATIONS_PATH};
+
+/// Флаги команды `publish`.
+pub const FLAGS: &[Flag] = &[DRY_RUN, DRAFT_PATH, POSTS_PATH, TRANSLATIONS_PATH];
 
 pub struct Publish {
     config: Config,
@@ -34,16 +38,14 @@ impl Command for Publish {
         details.push("post_path".to_string(), format!("{:#?}", grow_post_path));
         // перевод
         let translation = GrowPostTranslation { id: grow_post.slug.clone(), translated_value: grow_post.title.clone() };
-        let translation_path = config.get_translations_path_or_default()?
-            .join(grow_post.lang.to_lowercase())
-            .join("LC_MESSAGES/messages.po");
+        let translation_path = config.get_translation_file_path(grow_post.lang)?;
 
         details.push("translation_path".to_string(), format!("{:#?}", translation_path));
 
         if config.is_dry_run() {
             details.push("draft_post".to_string(), format!("{:#?}", draft_post));
             details.push("post".to_string(), format!("{:#?}", grow_post));
-            return Ok(CommandResult { command, details });
+            return Ok(CommandResult { command, details, ..CommandResult::default() });
         }
 
         let write_in_transaction = || -> Result<(), Error> {
@@ -54,6 +56,6 @@ impl Command for Publish {
 
         write_in_transaction()?;
 
-        Ok(CommandResult { command, details })
+        Ok(CommandResult { command, details, ..CommandResult::default() })
     }
 }
@@ -0,0 +1,85 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::build::Build;
+use crate::command::deploy::Deploy;
+use crate::command::index::Index;
+use crate::command::publish::Publish;
+use crate::command::{
+    BUILD_COMMAND_NAME, Command, CommandResult, DEPLOY_COMMAND_NAME, Details, Error, INDEX_COMMAND_NAME,
+    PUBLISH_COMMAND_NAME, RELEASE_COMMAND_NAME,
+};
+use crate::config::Config;
+use crate::command::args::{Flag, FlagValue, DRY_RUN};
+
+/// Флаги команды `release`, кроме флагов ее шагов.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    Flag { name: "--skip-publish", value: FlagValue::Switch, help: "skip the publish step." },
+    Flag { name: "--skip-index", value: FlagValue::Switch, help: "skip the index step." },
+    Flag { name: "--skip-build", value: FlagValue::Switch, help: "skip the build step." },
+    Flag { name: "--skip-deploy", value: FlagValue::Switch, help: "skip the deploy step." },
+];
+
+pub struct Release {
+    config: Config
+}
+
+impl Release {
+    pub fn new(config: Config) -> Box<Release> {
+        Box::new(Self { config })
+    }
+
+    /// Шаги релиза по порядку: имя шага и команда. Пропущенные через `--skip-<step>` шаги не создаются.
+    fn steps(&self) -> Vec<(&'static str, Option<Box<dyn Command>>)> {
+        let config = &self.config;
+        let step = |name: &'static str, create: fn(Config) -> Box<dyn Command>| {
+            let command = if config.is_step_skipped(name) { None } else { Some(create(config.clone())) };
+            (name, command)
+        };
+
+        vec![
+            step(PUBLISH_COMMAND_NAME, |config| Publish::new(config)),
+            step(INDEX_COMMAND_NAME, |config| Index::new(config)),
+            step(BUILD_COMMAND_NAME, |config| Build::new(config)),
+            step(DEPLOY_COMMAND_NAME, |config| Deploy::new(config)),
+        ]
+    }
+}
+
+/// Выполняет публикацию черновика, индексацию, сборку и выгрузку по очереди с теми же параметрами.
+/// Любой шаг можно пропустить параметром `--skip-<step>`, например `--skip-publish`.
+/// Если шаг завершился ошибкой, релиз останавливается, а в ошибке указано, какие шаги уже выполнены.
+impl Command for Release {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let mut details = Details::new();
+        let mut completed: Vec<&str> = Vec::new();
+
+        for (name, command) in self.steps() {
+            let Some(command) = command else {
+                details.push(String::from(name), String::from("skipped"));
+                continue;
+            };
+
+            let result = command.run().map_err(|e| Error::ReleaseFailed(
+                String::from(name), completed_steps(&completed), Box::new(e)
+            ))?;
+
+            details.push(String::from(name), String::from(if result.failed { "failed" } else { "done" }));
+            details.append_indented(result.details);
+
+            if result.failed {
+                let command = String::from(RELEASE_COMMAND_NAME);
+                return Ok(CommandResult { command, details, failed: true });
+            }
+
+            completed.push(name);
+        }
+
+        let command = String::from(RELEASE_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
+
+pub(crate) fn completed_steps(completed: &[&str]) -> String {
+    if completed.is_empty() { String::from("none") } else { completed.join(", ") }
+}
@@ -0,0 +1,106 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use crate::command::{Command, CommandResult, Details, Error, INDEX_COMMAND_NAME, RENAME_COMMAND_NAME};
+use crate::command::index::Index;
+use crate::config::Config;
+use crate::grow::RU_EN_MAPPING;
+use crate::grow::catalog::find_post;
+use crate::grow::lang::slugify;
+use crate::grow::post::{GrowPostTranslation, WriterWrapper};
+use crate::grow::redirects::{post_url, Redirects};
+use crate::command::args::{Flag, FlagValue, BLOG_PATH, DRY_RUN, LANG, POSTS_PATH, REDIRECTS_PATH, SLUG, TRANSLATIONS_PATH};
+
+/// Флаги команды `rename`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    SLUG,
+    Flag { name: "--to", value: FlagValue::Text("<slug>"), help: "new slug of the post (a-z, 0-9 and -)." },
+    LANG,
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    BLOG_PATH,
+    REDIRECTS_PATH,
+];
+
+pub struct Rename {
+    config: Config
+}
+
+impl Rename {
+    pub fn new(config: Config) -> Box<Rename> {
+        Box::new(Self { config })
+    }
+}
+
+/// Меняет slug записи: переименовывает файл записи, меняет `slugRu`/`slugEn` и msgid перевода заголовка,
+/// добавляет перенаправление со старого адреса на новый в файл перенаправлений и пересобирает индекс.
+/// Дата публикации и остальное содержимое записи не меняются.
+impl Command for Rename {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let slug = config.get_slug()?;
+        let new_slug = config.get_new_slug()?;
+
+        let expected = slugify(&new_slug, RU_EN_MAPPING);
+        if expected != new_slug {
+            return Err(Error::IncorrectFormat(format!("--to should be a slug of a-z, 0-9 and -, for example `{expected}`")));
+        }
+        if new_slug == slug {
+            return Err(Error::IncorrectFormat(format!("--to should differ from --slug `{slug}`")));
+        }
+
+        let posts_path = config.get_posts_path_or_default()?;
+        let (post_path, post) = find_post(&posts_path, &config.get_post_languages()?, &slug)?;
+        if let Ok((path, _)) = find_post(&posts_path, &[post.lang], &new_slug) {
+            return Err(Error::AlreadyExists(format!("post with slug `{new_slug}` ({})", path.display())));
+        }
+
+        let mut renamed = post.clone();
+        renamed.slug = new_slug.clone();
+        if renamed.title == slug {
+            renamed.title = new_slug.clone();
+        }
+        let renamed_path = renamed.build_post_path(&posts_path);
+        if renamed_path.exists() {
+            return Err(Error::AlreadyExists(renamed_path.display().to_string()));
+        }
+
+        let translation_path = config.get_translation_file_path(post.lang)?;
+        let redirects_path = config.get_redirects_path()?;
+        let (from, to) = (post_url(post.lang, &slug), post_url(post.lang, &new_slug));
+
+        let mut details = Details::new();
+        details.push(String::from("post_path"), format!("{} -> {}", post_path.display(), renamed_path.display()));
+        details.push(String::from("translation_path"), translation_path.display().to_string());
+        details.push(String::from("redirect"), format!("{from} -> {to} in {}", redirects_path.display()));
+
+        if !config.is_dry_run() {
+            WriterWrapper::write_file(&renamed_path, &renamed.to_string())?;
+            fs::remove_file(&post_path).map_err(Error::WriteFile)?;
+
+            let title = GrowPostTranslation::fetch_translations(&translation_path)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|t| t.id == slug)
+                .map(|t| t.translated_value);
+            if let Some(title) = title {
+                let translation = GrowPostTranslation { id: new_slug.clone(), translated_value: title };
+                GrowPostTranslation::replace_translation(&translation_path, &slug, Some(&translation))?;
+            } else {
+                details.push(String::from("translation"), format!("no msgid `{slug}`, skipped"));
+            }
+
+            let mut redirects = Redirects::load(&redirects_path)?;
+            redirects.add(&from, &to)?;
+            redirects.save(&redirects_path)?;
+        }
+
+        let index = Index::new(config.clone()).run()?;
+        details.push(String::from(INDEX_COMMAND_NAME), String::from(if index.failed { "failed" } else { "done" }));
+        details.append_indented(index.details);
+
+        let command = String::from(RENAME_COMMAND_NAME);
+        Ok(CommandResult { command, details, failed: index.failed })
+    }
+}
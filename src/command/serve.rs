@@ -0,0 +1,247 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::command::{Command, CommandResult, Error};
+use crate::config::Config;
+use crate::deploy::metadata::MetadataRules;
+use crate::deploy::verify::local_file_for;
+use crate::grow::post::{DraftPost, GrowPost};
+use crate::grow::preview::{draft_preview, error_preview, post_preview};
+use crate::grow::serdes::GrowDeserializer;
+use crate::command::args::{Flag, FlagValue, BUILD_PATH, DRAFT_PATH, MIME_TYPES};
+
+/// Адрес предпросмотра черновика.
+pub const DRAFT_PREVIEW_PATH: &str = "/__draft";
+/// Адрес, который возвращает версию файлов: страница перезагружается, когда версия меняется.
+const VERSION_PATH: &str = "/__version";
+/// Сколько ждать запрос в открытом соединении. Браузеры открывают соединения заранее и могут ничего не прислать.
+const READ_TIMEOUT_SECS: u64 = 5;
+
+/// Скрипт автоперезагрузки, который добавляется в html страницы.
+const RELOAD_SCRIPT: &str = r"<script>
+(function () {
+    var version = null;
+    setInterval(function () {
+        fetch('/__version').then(function (r) { return r.text(); }).then(function (v) {
+            if (version !== null && v !== version) { location.reload(); }
+            version = v;
+        }).catch(function () {});
+    }, 1000);
+})();
+</script>";
+
+/// Флаги команды `serve`.
+pub const FLAGS: &[Flag] = &[
+    BUILD_PATH,
+    DRAFT_PATH,
+    MIME_TYPES,
+    Flag { name: "--port", value: FlagValue::Number, help: "port to listen on localhost (SERVE_PORT, default 8000)." },
+];
+
+pub struct Serve {
+    config: Config
+}
+
+impl Serve {
+    pub fn new(config: Config) -> Box<Serve> {
+        Box::new(Self { config })
+    }
+}
+
+/// Локальный сервер для предпросмотра: отдает `ABS_BUILD_PATH` и черновик по адресу `/__draft`.
+/// Html страницы перезагружаются в браузере, когда меняется черновик или файлы сборки.
+impl Command for Serve {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let build_path = config.get_build_path_or_default()?;
+        let draft_path = config.get_draft_path_or_default().ok();
+        let rules = MetadataRules::new(&config.get_mime_types()?, &[])?;
+        let port = config.get_serve_port()?;
+
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| Error::Listen(port, e))?;
+        println!("Serving {} on http://127.0.0.1:{port}/ (draft preview at {DRAFT_PREVIEW_PATH}), press Ctrl+C to stop.", build_path.display());
+
+        let server = Arc::new(Server { build_path, draft_path, rules });
+        for stream in listener.incoming().flatten() {
+            // каждое соединение в своем потоке, чтобы открытое и молчащее соединение не задерживало остальные
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                // ошибка одного запроса, например закрытое браузером соединение, не должна останавливать сервер
+                let _ = server.handle(stream);
+            });
+        }
+
+        Ok(CommandResult::default())
+    }
+}
+
+struct Server {
+    build_path: PathBuf,
+    draft_path: Option<PathBuf>,
+    rules: MetadataRules,
+}
+
+struct Response {
+    status: &'static str,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl Server {
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)))?;
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // заголовки запроса не нужны, но их надо дочитать
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let response = match (parts.next(), parts.next()) {
+            (Some("GET" | "HEAD"), Some(url_path)) => self.respond(url_path),
+            _ => text_response("405 Method Not Allowed", "Only GET is supported"),
+        };
+
+        write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            response.status, response.content_type, response.body.len())?;
+        if !request_line.starts_with("HEAD") {
+            stream.write_all(&response.body)?;
+        }
+        stream.flush()
+    }
+
+    fn respond(&self, url_path: &str) -> Response {
+        let path = percent_decode(url_path.split(['?', '#']).next().unwrap_or_default());
+        let path = path.as_str();
+
+        if path == VERSION_PATH {
+            return text_response("200 OK", &self.version().to_string());
+        }
+
+        if path == DRAFT_PREVIEW_PATH {
+            return html_response(self.draft_page());
+        }
+
+        if path.split('/').any(|segment| segment == "..") {
+            return text_response("400 Bad Request", "Bad path");
+        }
+
+        let file = local_file_for(&self.build_path, path);
+        let Ok(body) = fs::read(&file) else {
+            return text_response("404 Not Found", &format!("{path} not found in {}", self.build_path.display()));
+        };
+
+        let extension = file.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        if extension == "html" {
+            return html_response(String::from_utf8_lossy(&body).to_string());
+        }
+
+        Response { status: "200 OK", content_type: self.rules.mime_type(extension), body }
+    }
+
+    /// Черновик, а если это уже опубликованная запись, то запись. Ошибки разбора показываются на странице.
+    fn draft_page(&self) -> String {
+        let Some(draft_path) = &self.draft_path else {
+            return error_preview("Set ABS_POST_DRAFT_FILE or --draft-path to preview a draft");
+        };
+
+        let content = match fs::read_to_string(draft_path) {
+            Ok(content) => content,
+            Err(e) => return error_preview(&format!("{}: {e}", draft_path.display())),
+        };
+
+        match DraftPost::deserialize(&content) {
+            Ok(draft) => draft_preview(&draft),
+            Err(draft_error) => match GrowPost::deserialize(&content) {
+                Ok(post) => post_preview(&post),
+                Err(_) => error_preview(&draft_error.to_string()),
+            },
+        }
+    }
+
+    /// Время последнего изменения черновика или файлов сборки в миллисекундах.
+    fn version(&self) -> u128 {
+        let mut newest = self.draft_path.as_deref().and_then(modified).unwrap_or(UNIX_EPOCH);
+        let mut dirs = vec![self.build_path.clone()];
+
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else { continue };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Some(time) = modified(&path) {
+                    newest = newest.max(time);
+                }
+            }
+        }
+
+        newest.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default()
+    }
+}
+
+/// Декодирует `%XX` в адресе один раз, чтобы проверять и искать файл по настоящему имени: `/%D1%84.html` - это
+/// `/ф.html`, а `/%2e%2e/` - это `/../`. Неверные последовательности остаются как есть.
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
+
+fn text_response(status: &'static str, body: &str) -> Response {
+    Response { status, content_type: String::from("text/plain; charset=utf-8"), body: body.as_bytes().to_vec() }
+}
+
+/// Html страница со скриптом автоперезагрузки.
+fn html_response(html: String) -> Response {
+    let body = match html.rfind("</body>") {
+        Some(position) => format!("{}{RELOAD_SCRIPT}\n{}", &html[..position], &html[position..]),
+        None => format!("{html}{RELOAD_SCRIPT}"),
+    };
+
+    Response { status: "200 OK", content_type: String::from("text/html; charset=utf-8"), body: body.into_bytes() }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::serve::percent_decode;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!("/ru/posts/ф.html", percent_decode("/ru/posts/%D1%84.html"));
+        assert_eq!("/../x", percent_decode("/%2e%2e/x"));
+        assert_eq!("/100%/a%zz", percent_decode("/100%/a%zz"));
+    }
+}
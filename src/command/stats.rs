@@ -0,0 +1,64 @@
+#![allow(clippy::must_use_candidate)]
+
+use crate::command::{Command, CommandResult, Details, Error, OutputFormat, STATS_COMMAND_NAME};
+use crate::config::Config;
+use crate::grow::post::GrowPost;
+use crate::grow::stats::{missing_translations, PostStats};
+use crate::command::args::{Flag, FlagValue, FORMAT, POSTS_PATH};
+
+/// Флаги команды `stats`.
+pub const FLAGS: &[Flag] = &[
+    POSTS_PATH,
+    Flag { name: "--top", value: FlagValue::Number, help: "number of top keywords to show (default 10)." },
+    Flag { name: "--missing-translations", value: FlagValue::Switch, help: "list posts without a post with the same slug in another language." },
+    FORMAT,
+];
+
+/// Сколько самых частых ключевых слов показывать по умолчанию.
+const DEFAULT_TOP_KEYWORDS: usize = 10;
+
+pub struct Stats {
+    config: Config
+}
+
+impl Stats {
+    pub fn new(config: Config) -> Box<Stats> {
+        Box::new(Self { config })
+    }
+}
+
+/// Выводит статистику по всем записям: количество записей по годам и месяцам для каждого языка,
+/// количество слов, среднее время чтения, частые ключевые слова и самые длинные перерывы между записями.
+/// С `--missing-translations` еще и записи без перевода на другой язык.
+impl Command for Stats {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let posts_path = config.get_posts_path_or_default()?;
+        let langs = config.available_languages();
+
+        let mut posts: Vec<GrowPost> = Vec::new();
+        for lang in &langs {
+            posts.extend(GrowPost::fetch_posts_by_lang(&posts_path, *lang)?);
+        }
+
+        let langs: Vec<String> = langs.iter().map(|lang| lang.to_lowercase()).collect();
+        let top = config.get_top().unwrap_or(DEFAULT_TOP_KEYWORDS);
+        let mut stats = PostStats::collect(&posts, &langs, top);
+        if config.is_missing_translations() {
+            stats.missing_translations = missing_translations(&posts, &langs);
+        }
+
+        let mut details = Details::new();
+        if config.get_output_format()? == OutputFormat::Json {
+            details.push(String::new(), stats.to_json());
+            return Ok(CommandResult { details, ..CommandResult::default() });
+        }
+
+        for (id, message) in stats.to_lines() {
+            details.push(id, format!(" {message}"));
+        }
+
+        let command = String::from(STATS_COMMAND_NAME);
+        Ok(CommandResult { command, details, ..CommandResult::default() })
+    }
+}
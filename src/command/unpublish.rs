@@ -0,0 +1,121 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::command::{Command, CommandResult, Details, Error, INDEX_COMMAND_NAME, UNPUBLISH_COMMAND_NAME};
+use crate::command::index::Index;
+use crate::command::release::completed_steps;
+use crate::config::Config;
+use crate::grow::catalog::find_post;
+use crate::grow::post::{GrowPostTranslation, WriterWrapper};
+use crate::command::args::{Flag, FlagValue, DRAFT_PATH, DRY_RUN, LANG, POSTS_PATH, SLUG, TRANSLATIONS_PATH};
+
+/// Флаги команды `unpublish`.
+pub const FLAGS: &[Flag] = &[
+    DRY_RUN,
+    SLUG,
+    LANG,
+    POSTS_PATH,
+    TRANSLATIONS_PATH,
+    DRAFT_PATH,
+    Flag { name: "--archive", value: FlagValue::Text("<dir>"), help: "move the post file to <dir>/<lang>/ instead of deleting it." },
+    Flag { name: "--to-draft", value: FlagValue::Switch, help: "write the post back to the draft file (or <slug>.md in the drafts dir)." },
+];
+
+pub struct Unpublish {
+    config: Config
+}
+
+impl Unpublish {
+    pub fn new(config: Config) -> Box<Unpublish> {
+        Box::new(Self { config })
+    }
+}
+
+/// Снимает запись с публикации: удаляет файл записи (или переносит его в `--archive`), удаляет перевод
+/// заголовка из `messages.po` и пересобирает индекс. С `--to-draft` запись сначала сохраняется черновиком.
+impl Command for Unpublish {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let slug = config.get_slug()?;
+        let (post_path, post) = find_post(&config.get_posts_path_or_default()?, &config.get_post_languages()?, &slug)?;
+        let translation_path = config.get_translation_file_path(post.lang)?;
+
+        let mut details = Details::new();
+        details.push(String::from("post_path"), post_path.display().to_string());
+        details.push(String::from("translation_path"), translation_path.display().to_string());
+
+        let archive_path = config.get_archive_path()
+            .map(|dir| dir.join(post.lang.to_lowercase()).join(post_path.file_name().unwrap_or_default()));
+        if let Some(archive_path) = &archive_path {
+            if archive_path.exists() {
+                return Err(Error::AlreadyExists(archive_path.display().to_string()));
+            }
+            details.push(String::from("archive_path"), archive_path.display().to_string());
+        }
+
+        let draft = if config.is_to_draft() {
+            let title = GrowPostTranslation::fetch_translations(&translation_path)?.into_iter()
+                .find(|t| t.id == slug)
+                .map_or(slug.clone(), |t| t.translated_value);
+            let draft_path = draft_file_path(&config.get_draft_path_or_default()?, &slug);
+            if draft_path.exists() {
+                return Err(Error::AlreadyExists(draft_path.display().to_string()));
+            }
+            details.push(String::from("draft_path"), draft_path.display().to_string());
+            Some((draft_path, post.to_draft(title)))
+        } else {
+            None
+        };
+
+        let mut completed: Vec<&str> = Vec::new();
+        if !config.is_dry_run() {
+            // черновик сохраняется до удаления записи, чтобы при ошибке не потерять текст
+            if let Some((draft_path, draft)) = &draft {
+                WriterWrapper::write_file(draft_path, &draft.to_string())?;
+                completed.push("draft");
+            }
+
+            match &archive_path {
+                Some(archive_path) => {
+                    move_file(&post_path, archive_path).map_err(|e| step_failed("archive", &completed, e))?;
+                    completed.push("archive");
+                }
+                None => {
+                    fs::remove_file(&post_path).map_err(|e| step_failed("remove", &completed, Error::WriteFile(e)))?;
+                    completed.push("remove");
+                }
+            }
+
+            GrowPostTranslation::replace_translation(&translation_path, &slug, None)
+                .map_err(|e| step_failed("translation", &completed, e))?;
+            completed.push("translation");
+        }
+
+        // индекс строится по оставшимся записям, поэтому собрать его до удаления нельзя: ошибка называет
+        // уже сделанные шаги, чтобы их можно было проверить или откатить вручную
+        let index = Index::new(config.clone()).run()
+            .map_err(|e| step_failed(INDEX_COMMAND_NAME, &completed, e))?;
+        details.push(String::from(INDEX_COMMAND_NAME), String::from(if index.failed { "failed" } else { "done" }));
+        details.append_indented(index.details);
+
+        let command = String::from(UNPUBLISH_COMMAND_NAME);
+        Ok(CommandResult { command, details, failed: index.failed })
+    }
+}
+
+fn step_failed(step: &str, completed: &[&str], error: Error) -> Error {
+    Error::UnpublishFailed(String::from(step), completed_steps(completed), Box::new(error))
+}
+
+/// Путь черновика: сам `draft_path` или `<slug>.md`, если `draft_path` - каталог черновиков.
+fn draft_file_path(draft_path: &Path, slug: &str) -> PathBuf {
+    if draft_path.is_dir() { draft_path.join(format!("{slug}.md")) } else { draft_path.to_path_buf() }
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), Error> {
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir).map_err(Error::WriteFile)?;
+    }
+    fs::rename(from, to).map_err(Error::WriteFile)
+}
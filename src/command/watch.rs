@@ -0,0 +1,102 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use chrono::Local;
+use colored::Colorize;
+use crate::command::{Command, CommandResult, Details, Error, WATCH_COMMAND_NAME};
+use crate::config::Config;
+use crate::grow::post::DraftPost;
+use crate::grow::serdes::GrowDeserializer;
+use crate::command::args::{Flag, FlagValue, DRAFT_PATH};
+
+/// Как часто проверять время изменения черновиков.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DRAFT_EXTENSION: &str = "md";
+
+/// Флаги команды `watch`.
+pub const FLAGS: &[Flag] = &[
+    DRAFT_PATH,
+    Flag { name: "--once", value: FlagValue::Switch, help: "validate once and exit instead of watching." },
+];
+
+pub struct Watch {
+    config: Config
+}
+
+impl Watch {
+    pub fn new(config: Config) -> Box<Watch> {
+        Box::new(Self { config })
+    }
+}
+
+/// Следит за черновиком (`ABS_POST_DRAFT_FILE`) или за всеми `.md` файлами каталога черновиков и после каждого
+/// сохранения проверяет, что черновик разбирается так же, как при `publish`: длина заголовка и описания,
+/// количество ключевых слов, неизвестные ключи. С `--once` проверяет один раз и завершается.
+impl Command for Watch {
+    fn run(&self) -> Result<CommandResult, Error> {
+        let config = &self.config;
+        let draft_path = config.get_draft_path_or_default()?;
+
+        if config.is_once() {
+            let mut details = Details::new();
+            let mut failed = false;
+
+            for path in drafts(&draft_path)? {
+                match validate(&path) {
+                    Ok(()) => details.push(path.display().to_string(), String::from("OK")),
+                    Err(e) => {
+                        failed = true;
+                        details.push(path.display().to_string(), e.to_string());
+                    }
+                }
+            }
+
+            let command = String::from(WATCH_COMMAND_NAME);
+            return Ok(CommandResult { command, details, failed });
+        }
+
+        println!("Watching {} for changes, press Ctrl+C to stop.", draft_path.display());
+        let mut seen: HashMap<PathBuf, SystemTime> = HashMap::new();
+
+        loop {
+            for path in drafts(&draft_path)? {
+                let Ok(modified) = path.metadata().and_then(|m| m.modified()) else { continue };
+                if seen.insert(path.clone(), modified) == Some(modified) {
+                    continue;
+                }
+
+                let time = Local::now().format("%H:%M:%S");
+                match validate(&path) {
+                    Ok(()) => println!("{time} {} {}", path.display(), "OK".green()),
+                    Err(e) => println!("{time} {} {}", path.display(), e.to_string().red()),
+                }
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Черновики для проверки: сам файл или `.md` файлы каталога, отсортированные по имени.
+fn drafts(draft_path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if !draft_path.is_dir() {
+        return Ok(vec![draft_path.to_path_buf()]);
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(draft_path).map_err(Error::ReadDir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == DRAFT_EXTENSION))
+        .collect();
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn validate(path: &Path) -> Result<(), Error> {
+    let content = fs::read_to_string(path).map_err(Error::ReadFile)?;
+    DraftPost::deserialize(content.as_str()).map(|_| ())
+}
@@ -1,27 +1,50 @@
 #![allow(clippy::must_use_candidate)]
 #![allow(clippy::or_fun_call)]
 
-use crate::command::Error;
+use crate::command::{Error, OutputFormat};
+use crate::command::args::CommandSpec;
 use crate::grow::lang::Lang;
+use crate::grow::{ISO8601_DATE_FORMAT, TRANSLATION_FILE_PATH};
+use crate::grow::catalog::{PostFilter, SortBy};
+use crate::grow::redirects::{RedirectsFormat, REDIRECTS_FILE_NAME};
+use chrono::NaiveDate;
 use std::collections::HashMap;
 use std::env;
-use std::env::Args;
+use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::time::Duration;
+use crate::deploy::{DEFAULT_COMPRESS_MIN_SIZE, DEFAULT_LOCK_TTL_SECS, DEFAULT_PRUNE_MAX_PERCENT, DEFAULT_VERIFY_PATHS};
+use crate::deploy::retry::RetryPolicy;
+use crate::config::profile::Profile;
+use crate::config::secrets::Secrets;
+
+pub mod profile;
+pub mod secrets;
+pub mod settings;
 
 pub const VERSION: &str = "0.1.4";
 
 pub const PARAMETER_KEY_VALUE_DELIMITER: &str = "=";
 pub const PARAMETER_PREFIX: &str = "--";
 
+pub const DEFAULT_BUILD_COMMAND: &str = "grow build";
+pub const DEFAULT_HISTORY_SIZE: u64 = 10;
+pub const DEFAULT_SERVE_PORT: u64 = 8000;
+pub const DEFAULT_RETRY_ATTEMPTS: u64 = 3;
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 500;
+pub const DEFAULT_PER_PAGE: u64 = 20;
+pub const DEFAULT_EDITOR: &str = "vi";
+/// Каталог историй выгрузок в каталоге кэша.
+pub const RELEASES_DIR_NAME: &str = "releases";
+/// Имя истории, если не задан ни контейнер, ни профиль.
+const DEFAULT_HISTORY_NAME: &str = "default";
+
+/// Настройки команды. Значение ищется по порядку: параметр командной строки, переменная окружения
+/// (в том числе из .env), профиль из файла настроек, значение по умолчанию.
+#[derive(Clone, Default)]
 pub struct Config {
     args_map: HashMap<String, String>,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        let args_map: HashMap<String, String> = HashMap::new();
-        Self { args_map }
-    }
+    profile: Option<Profile>,
 }
 
 pub struct DeployConfig {
@@ -32,44 +55,48 @@ pub struct DeployConfig {
     pub destination: String,
 }
 
-impl ToString for DeployConfig {
-    fn to_string(&self) -> String {
+impl DeployConfig {
+    /// Куда выгружается сайт: адрес API, аккаунт и контейнер, без реквизитов доступа.
+    pub fn target(&self) -> String {
+        format!("{} SEL_{}/{}", self.destination, self.account_id, self.container_id)
+    }
+}
+
+impl Display for DeployConfig {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
         unimplemented!("DeployConfig contains sensitive data!")
     }
 }
 
 //todo WHY fn in Config is not static?
 impl Config {
+    /// Языки блога из `BLOG_LANGUAGES` (или `languages` профиля) через запятую, по умолчанию ru и en.
+    /// Неизвестные языки пропускаются.
     pub fn available_languages(&self) -> Vec<Lang> {
-        vec![Lang::Ru, Lang::En]
-    }
-
-    /// # Errors
-    ///
-    /// Вернет `Error` если при парсинге входных параметров ключи имеют неверный формат.
-    /// Они должны иметь формат --key-name или --key-name=value.
-    pub fn from_args(args: Args) -> Result<Self, Error> {
-        let mut args_map = HashMap::new();
-        for param in args {
-            if param.contains(PARAMETER_KEY_VALUE_DELIMITER) {
-                let (k, v) = param.split_once(PARAMETER_KEY_VALUE_DELIMITER).ok_or(
-                    Error::IncorrectFormat(format!(
-                        "Parameter key values should be delimited by {}",
-                        PARAMETER_KEY_VALUE_DELIMITER
-                    )),
-                )?;
-                args_map.insert(k.to_string(), v.to_string());
-                continue;
-            }
-
-            if param.contains(PARAMETER_PREFIX) {
-                args_map.insert(param, String::from("true"));
-                continue;
-            }
-            return Err(Error::Parse());
-        }
+        let languages: Vec<Lang> = self.get_list("--languages", "BLOG_LANGUAGES").iter()
+            .filter_map(|lang| lang.parse().ok())
+            .collect();
+
+        if languages.is_empty() { vec![Lang::Ru, Lang::En] } else { languages }
+    }
+
+    /// Разбирает аргументы команды `spec`. Флаги должны быть объявлены в описании команды.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если флаг неизвестен команде, аргумент не начинается с `--` или значение флага
+    /// имеет неверный формат. Флаги должны иметь формат --key-name или --key-name=value.
+    pub fn from_args(spec: &CommandSpec, args: impl Iterator<Item = String>) -> Result<Self, Error> {
+        let args_map = spec.parse(args)?;
+        let profile = Profile::load(args_map.get("--profile").map(String::as_str))?;
+        Ok(Self { args_map, profile })
+    }
 
-        Ok(Self { args_map })
+    /// Значение переменной окружения `env_key`, а если она не задана или пустая, то значение из профиля.
+    pub fn get_var(&self, env_key: &str) -> Option<String> {
+        env::var(env_key).ok()
+            .filter(|value| !value.is_empty())
+            .or(self.profile.as_ref().and_then(|profile| profile.get(env_key).cloned()))
     }
 
     pub fn is_dry_run(&self) -> bool {
@@ -84,16 +111,7 @@ impl Config {
     ///
     /// Вернет `Error` если переменная окружения `ABS_POST_DRAFT_FILE` не задана или имеет нулевую длинну.
     pub fn get_draft_path_or_default(&self) -> Result<PathBuf, Error> {
-        let default_draft_path = env::var("ABS_POST_DRAFT_FILE")?;
-
-        if default_draft_path.is_empty() {
-            return Err(Error::EmptyValue(String::from("ABS_POST_DRAFT_FILE")));
-        }
-
-        let draft_path = self.args_map.get("--draft-path")
-            .unwrap_or(&default_draft_path);
-
-        Ok(PathBuf::from(draft_path))
+        self.get_path("--draft-path", "ABS_POST_DRAFT_FILE")
     }
 
     /// Возвращает путь до post записей
@@ -103,16 +121,7 @@ impl Config {
     ///
     /// Вернет `Error` если переменная окружения `ABS_POSTS_PATH` не задана или имеет нулевую длину.
     pub fn get_posts_path_or_default(&self) -> Result<PathBuf, Error> {
-        let default_posts_path = env::var("ABS_POSTS_PATH")?;
-
-        if default_posts_path.is_empty() {
-            return Err(Error::EmptyValue(String::from("ABS_POSTS_PATH")));
-        }
-
-        let path = self.args_map.get("--posts-path")
-            .unwrap_or(&default_posts_path);
-
-        Ok(PathBuf::from(&path))
+        self.get_path("--posts-path", "ABS_POSTS_PATH")
     }
 
     /// Возвращает путь до переводов в зависимости от языка записи.
@@ -122,16 +131,16 @@ impl Config {
     ///
     /// Вернет Error если переменная окружения `ABS_TRANSLATIONS_PATH` не задана или имеет нулевую длину.
     pub fn get_translations_path_or_default(&self) -> Result<PathBuf, Error> {
-        let default_translation_path = env::var("ABS_TRANSLATIONS_PATH")?;
-
-        if default_translation_path.is_empty() {
-            return Err(Error::EmptyValue(String::from("ABS_TRANSLATIONS_PATH")));
-        }
-
-        let path = self.args_map.get("--translations-path")
-            .unwrap_or(&default_translation_path);
+        self.get_path("--translations-path", "ABS_TRANSLATIONS_PATH")
+    }
 
-        Ok(PathBuf::from(&path))
+    /// Возвращает путь до файла переводов `messages.po` языка `lang` в каталоге переводов.
+    ///
+    /// # Errors
+    ///
+    /// Вернет Error если каталог переводов не задан.
+    pub fn get_translation_file_path(&self, lang: Lang) -> Result<PathBuf, Error> {
+        Ok(self.get_translations_path_or_default()?.join(lang.to_lowercase()).join(TRANSLATION_FILE_PATH))
     }
 
     /// Возвращает путь до файла с индексом в зависимости от языка записи.
@@ -141,16 +150,7 @@ impl Config {
     ///
     /// Вернет Error если переменная окружения `ABS_INDEX_FILE` не задана или имеет нулевую длину.
     pub fn get_index_file_path_or_default(&self) -> Result<PathBuf, Error> {
-        let default_path = env::var("ABS_INDEX_FILE")?;
-
-        if default_path.is_empty() {
-            return Err(Error::EmptyValue(String::from("ABS_INDEX_FILE")));
-        }
-
-        let resolved_path = self.args_map.get("--index-path")
-            .unwrap_or(&default_path);
-
-        Ok(PathBuf::from(&resolved_path))
+        self.get_path("--index-path", "ABS_INDEX_FILE")
     }
 
     /// Возвращает путь до директории, в которой лежат подготовленные для выгрузки данные.
@@ -160,25 +160,38 @@ impl Config {
     ///
     /// Вернет Error если переменная окружения `ABS_BUILD_PATH` не задана или имеет нулевую длину.
     pub fn get_build_path_or_default(&self) -> Result<PathBuf, Error> {
-        let default_path = env::var("ABS_BUILD_PATH")?;
-
-        if default_path.is_empty() {
-            return Err(Error::EmptyValue(String::from("ABS_BUILD_PATH")));
-        }
+        self.get_path("--build-path", "ABS_BUILD_PATH")
+    }
 
-        let resolved_path = self.args_map.get("--build-path")
-            .unwrap_or(&default_path);
+    /// Возвращает путь до каталога статического сайта из параметра `--blog-path` или переменной окружения
+    /// `ABS_BASE_PATH_TO_BLOG`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет Error если переменная окружения `ABS_BASE_PATH_TO_BLOG` не задана или имеет нулевую длину.
+    pub fn get_blog_path_or_default(&self) -> Result<PathBuf, Error> {
+        self.get_path("--blog-path", "ABS_BASE_PATH_TO_BLOG")
+    }
 
-        Ok(PathBuf::from(&resolved_path))
+    /// Команда сборки сайта из параметра `--build-command` или `BUILD_COMMAND`, по умолчанию `grow build`.
+    pub fn get_build_command(&self) -> String {
+        self.args_map.get("--build-command").cloned()
+            .or(self.get_var("BUILD_COMMAND"))
+            .filter(|command| !command.trim().is_empty())
+            .unwrap_or(String::from(DEFAULT_BUILD_COMMAND))
     }
 
     /// Возвращает Deploy config, который содержит реквизиты для доступа к облачному хранилищу и выполнения деплоя.
+    /// Каждое значение берется из переменной окружения, команды `*_COMMAND` или зашифрованного файла
+    /// `DEPLOY_SECRETS_FILE`, см. `Secrets`.
     pub fn get_deploy_config(&self) -> Result<DeployConfig, Error> {
-        let account_id = env::var("DEPLOY_ACCOUNT")?;
-        let username = env::var("DEPLOY_USERNAME")?;
-        let password = env::var("DEPLOY_PASSWORD")?;
-        let container_id = env::var("DEPLOY_CONTAINER")?;
-        let destination = env::var("DEPLOY_DEST")?;
+        let lookup = |key: &str| self.get_var(key);
+        let secrets = Secrets::new(&lookup);
+        let account_id = secrets.get("DEPLOY_ACCOUNT")?;
+        let username = secrets.get("DEPLOY_USERNAME")?;
+        let password = secrets.get("DEPLOY_PASSWORD")?;
+        let container_id = secrets.get("DEPLOY_CONTAINER")?;
+        let destination = secrets.get("DEPLOY_DEST")?;
 
         if account_id.is_empty() || username.is_empty() || password.is_empty() || container_id.is_empty() || destination.is_empty() {
             return Err(
@@ -188,4 +201,447 @@ impl Config {
 
         Ok(DeployConfig{ account_id, username, password, container_id, destination })
     }
+
+    /// Возвращает политику повторов запросов к облачному хранилищу.
+    /// Количество попыток берется из `--retries` или `DEPLOY_RETRIES` (по умолчанию 3),
+    /// начальная задержка в миллисекундах из `--retry-delay` или `DEPLOY_RETRY_DELAY_MS` (по умолчанию 500).
+    /// Каждая следующая задержка в два раза больше предыдущей.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значения не являются целыми числами.
+    pub fn get_retry_policy(&self) -> Result<RetryPolicy, Error> {
+        let attempts = self.get_number("--retries", "DEPLOY_RETRIES", DEFAULT_RETRY_ATTEMPTS)?;
+        let delay = self.get_number("--retry-delay", "DEPLOY_RETRY_DELAY_MS", DEFAULT_RETRY_DELAY_MS)?;
+
+        Ok(RetryPolicy::new(u32::try_from(attempts).unwrap_or(u32::MAX), Duration::from_millis(delay)))
+    }
+
+    /// Выгружать файлы по одному вместо архива с распаковкой на стороне хранилища.
+    /// Задается параметром `--per-file` или переменной окружения `DEPLOY_PER_FILE=true`.
+    pub fn is_per_file_upload(&self) -> bool {
+        self.get_flag("--per-file", "DEPLOY_PER_FILE")
+    }
+
+    /// Удалять из хранилища объекты, которых нет в сборке (`--prune` или `DEPLOY_PRUNE=true`).
+    pub fn is_prune_enabled(&self) -> bool {
+        self.get_flag("--prune", "DEPLOY_PRUNE")
+    }
+
+    /// Пропустить шаг `step` команды `release` (`--skip-publish`, `--skip-index`, `--skip-build`, `--skip-deploy`).
+    pub fn is_step_skipped(&self, step: &str) -> bool {
+        self.args_map.contains_key(&format!("--skip-{step}"))
+    }
+
+    /// Порт локального сервера предпросмотра: `--port` или `SERVE_PORT` (по умолчанию 8000).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значение не является номером порта.
+    pub fn get_serve_port(&self) -> Result<u16, Error> {
+        let port = self.get_number("--port", "SERVE_PORT", DEFAULT_SERVE_PORT)?;
+        u16::try_from(port).map_err(|_| Error::IncorrectFormat(format!("--port (SERVE_PORT) should be less than 65536, got `{port}`")))
+    }
+
+    /// Выполнить проверку один раз вместо постоянного наблюдения (`--once`).
+    pub fn is_once(&self) -> bool {
+        self.args_map.contains_key("--once")
+    }
+
+    /// Исправить найденные проблемы, которые можно исправить без потери данных (`--fix`).
+    pub fn is_fix(&self) -> bool {
+        self.args_map.contains_key("--fix")
+    }
+
+    /// Выполнить действие несмотря на защитные проверки (`--force`).
+    pub fn is_force(&self) -> bool {
+        self.args_map.contains_key("--force")
+    }
+
+    /// Сколько процентов объектов хранилища можно удалить без `--force`:
+    /// `--prune-max-percent` или `DEPLOY_PRUNE_MAX_PERCENT` (по умолчанию 20).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значение не является целым числом.
+    pub fn get_prune_max_percent(&self) -> Result<u64, Error> {
+        self.get_number("--prune-max-percent", "DEPLOY_PRUNE_MAX_PERCENT", DEFAULT_PRUNE_MAX_PERCENT)
+    }
+
+    /// Через сколько блокировка выгрузки считается брошенной: `--lock-ttl` или `DEPLOY_LOCK_TTL`
+    /// в секундах (по умолчанию 1800).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значение не является целым числом.
+    pub fn get_lock_ttl(&self) -> Result<Duration, Error> {
+        Ok(Duration::from_secs(self.get_number("--lock-ttl", "DEPLOY_LOCK_TTL", DEFAULT_LOCK_TTL_SECS)?))
+    }
+
+    /// Создавать сжатые копии файлов сборки перед выгрузкой (`--compress` или `DEPLOY_COMPRESS=true`).
+    pub fn is_compress_enabled(&self) -> bool {
+        self.get_flag("--compress", "DEPLOY_COMPRESS")
+    }
+
+    /// Создавать кроме `.gz` еще и `.br` копии (`--brotli` или `COMPRESS_BROTLI=true`).
+    pub fn is_brotli_enabled(&self) -> bool {
+        self.get_flag("--brotli", "COMPRESS_BROTLI")
+    }
+
+    /// Минимальный размер файла в байтах для создания сжатой копии: `--min-size` или `COMPRESS_MIN_SIZE`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значение не является целым числом.
+    pub fn get_compress_min_size(&self) -> Result<u64, Error> {
+        self.get_number("--min-size", "COMPRESS_MIN_SIZE", DEFAULT_COMPRESS_MIN_SIZE)
+    }
+
+    /// Возвращает адрес сайта для проверки после выгрузки: `--site-url`, `DEPLOY_SITE_URL` или адрес выгрузки
+    /// `destination` (`DEPLOY_DEST` из уже полученного `DeployConfig`).
+    pub fn get_site_url(&self, destination: &str) -> String {
+        self.args_map.get("--site-url").cloned()
+            .or(self.get_var("DEPLOY_SITE_URL"))
+            .unwrap_or(destination.to_string())
+    }
+
+    /// Возвращает список адресов (относительно адреса сайта), которые проверяются после выгрузки.
+    /// Задается через `--verify-paths` или `DEPLOY_VERIFY_PATHS` списком через запятую,
+    /// `@newest` означает самую новую запись.
+    pub fn get_verify_paths(&self) -> Vec<String> {
+        let paths = self.get_list("--verify-paths", "DEPLOY_VERIFY_PATHS");
+        if paths.is_empty() {
+            return DEFAULT_VERIFY_PATHS.split(',').map(ToString::to_string).collect();
+        }
+        paths
+    }
+
+    /// Пропустить проверку сайта после выгрузки (`--no-verify`).
+    pub fn is_verify_skipped(&self) -> bool {
+        self.args_map.contains_key("--no-verify")
+    }
+
+    /// Возвращает путь до локальной истории выгрузок: `--history-path`, `DEPLOY_HISTORY_PATH`
+    /// или `releases/<контейнер>` в каталоге кэша, чтобы истории разных сайтов не смешивались. Контейнер
+    /// берется из `DEPLOY_CONTAINER` без `*_COMMAND` и файла секретов, а если его нет, то имя профиля.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если путь не задан и не удалось определить каталог кэша.
+    pub fn get_history_path_or_default(&self) -> Result<PathBuf, Error> {
+        if let Ok(path) = self.get_path("--history-path", "DEPLOY_HISTORY_PATH") {
+            return Ok(path);
+        }
+
+        let name = self.get_var("DEPLOY_CONTAINER")
+            .or(self.profile.as_ref().map(|profile| profile.name.clone()))
+            .unwrap_or(String::from(DEFAULT_HISTORY_NAME))
+            .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_', "-");
+        Ok(cache_dir()?.join(RELEASES_DIR_NAME).join(name))
+    }
+
+    /// Сколько последних версий хранить в истории выгрузок (`DEPLOY_HISTORY_SIZE`, по умолчанию 10).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значение не является целым числом.
+    pub fn get_history_size(&self) -> Result<usize, Error> {
+        let size = self.get_number("--history-size", "DEPLOY_HISTORY_SIZE", DEFAULT_HISTORY_SIZE)?;
+        Ok(usize::try_from(size).unwrap_or(usize::MAX))
+    }
+
+    /// Возвращает id версии из параметра `--to`.
+    pub fn get_release_id(&self) -> Option<String> {
+        self.args_map.get("--to").cloned()
+    }
+
+    /// Возвращает правила исключения файлов сборки из выгрузки в формате gitignore:
+    /// `--exclude` или `DEPLOY_EXCLUDE` через запятую.
+    pub fn get_exclude_patterns(&self) -> Vec<String> {
+        self.get_list("--exclude", "DEPLOY_EXCLUDE")
+    }
+
+    /// Возвращает правила, которые возвращают в выгрузку исключенные файлы:
+    /// `--include` или `DEPLOY_INCLUDE` через запятую.
+    pub fn get_include_patterns(&self) -> Vec<String> {
+        self.get_list("--include", "DEPLOY_INCLUDE")
+    }
+
+    /// Возвращает переопределения типов содержимого: `DEPLOY_MIME_TYPES` в формате
+    /// `json=application/json;webmanifest=application/manifest+json`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если правило не в формате `ext=type`.
+    pub fn get_mime_types(&self) -> Result<Vec<(String, String)>, Error> {
+        self.get_pairs("--mime-types", "DEPLOY_MIME_TYPES")
+    }
+
+    /// Возвращает правила `Cache-Control`: `DEPLOY_CACHE_CONTROL` в формате
+    /// `*.css=public, max-age=31536000;*.html=no-cache`. Применяется первое совпавшее правило.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если правило не в формате `glob=value`.
+    pub fn get_cache_control_rules(&self) -> Result<Vec<(String, String)>, Error> {
+        self.get_pairs("--cache-control", "DEPLOY_CACHE_CONTROL")
+    }
+
+    /// Возвращает формат вывода отчета из параметра `--format` (`text` по умолчанию или `json`).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если формат неизвестен.
+    pub fn get_output_format(&self) -> Result<OutputFormat, Error> {
+        match self.args_map.get("--format").map(String::as_str) {
+            None | Some("text") => Ok(OutputFormat::Text),
+            Some("json") => Ok(OutputFormat::Json),
+            Some(unknown) => Err(Error::IncorrectFormat(format!("Unknown output format `{unknown}`, expected text or json"))),
+        }
+    }
+
+    /// Возвращает фильтр записей из параметров `--lang`, `--from`, `--to` (даты в формате 2022-01-31),
+    /// `--keyword` и `--author`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если язык неизвестен или дата в неверном формате.
+    pub fn get_post_filter(&self) -> Result<PostFilter, Error> {
+        Ok(PostFilter {
+            lang: self.get_lang()?,
+            from: self.get_date("--from")?,
+            to: self.get_date("--to")?,
+            keyword: self.args_map.get("--keyword").cloned(),
+            author: self.args_map.get("--author").cloned(),
+        })
+    }
+
+    /// Язык записи из параметра `--lang`, один из языков блога (`BLOG_LANGUAGES` или профиль).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если язык неизвестен или блог на нем не ведется.
+    pub fn get_lang(&self) -> Result<Option<Lang>, Error> {
+        let Some(value) = self.args_map.get("--lang") else {
+            return Ok(None);
+        };
+
+        let languages = self.available_languages();
+        match value.parse::<Lang>() {
+            Ok(lang) if languages.contains(&lang) => Ok(Some(lang)),
+            _ => Err(Error::IncorrectFormat(format!(
+                "--lang should be one of {}, got `{value}`",
+                languages.iter().map(|lang| lang.to_lowercase()).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+
+    /// Языки, в которых искать запись: `--lang`, если задан, иначе все языки блога.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если язык неизвестен.
+    pub fn get_post_languages(&self) -> Result<Vec<Lang>, Error> {
+        Ok(self.get_lang()?.map_or(self.available_languages(), |lang| vec![lang]))
+    }
+
+    /// Slug записи из параметра `--slug`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если параметр не задан или пустой.
+    pub fn get_slug(&self) -> Result<String, Error> {
+        self.args_map.get("--slug")
+            .map(|slug| slug.trim().to_string())
+            .filter(|slug| !slug.is_empty())
+            .ok_or(Error::EmptyValue(String::from("--slug")))
+    }
+
+    /// Редактор записи из параметра `--editor` или переменных окружения `VISUAL` и `EDITOR`, по умолчанию `vi`.
+    pub fn get_editor(&self) -> String {
+        self.args_map.get("--editor").cloned()
+            .or(self.get_var("VISUAL"))
+            .or(self.get_var("EDITOR"))
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or(String::from(DEFAULT_EDITOR))
+    }
+
+    /// Новый slug записи из параметра `--to`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если параметр не задан или пустой.
+    pub fn get_new_slug(&self) -> Result<String, Error> {
+        self.args_map.get("--to")
+            .map(|slug| slug.trim().to_string())
+            .filter(|slug| !slug.is_empty())
+            .ok_or(Error::EmptyValue(String::from("--to")))
+    }
+
+    /// Файл перенаправлений со старых адресов записей на новые: `--redirects-path` или `REDIRECTS_FILE`,
+    /// по умолчанию `redirects.txt` в каталоге сайта.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не задан ни файл, ни каталог сайта `ABS_BASE_PATH_TO_BLOG`.
+    pub fn get_redirects_path(&self) -> Result<PathBuf, Error> {
+        match self.args_map.get("--redirects-path").cloned().or(self.get_var("REDIRECTS_FILE")) {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => Ok(self.get_blog_path_or_default()?.join(REDIRECTS_FILE_NAME)),
+        }
+    }
+
+    /// В каком виде выводить перенаправления при сборке: `--redirects` или `REDIRECTS_FORMAT`
+    /// (`html` по умолчанию или `nginx`).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если формат неизвестен.
+    pub fn get_redirects_format(&self) -> Result<RedirectsFormat, Error> {
+        match self.args_map.get("--redirects").cloned().or(self.get_var("REDIRECTS_FORMAT")).as_deref() {
+            None | Some("html") => Ok(RedirectsFormat::Html),
+            Some("nginx") => Ok(RedirectsFormat::Nginx),
+            Some(unknown) => Err(Error::IncorrectFormat(format!("Unknown redirects format `{unknown}`, expected html or nginx"))),
+        }
+    }
+
+    /// Каталог, в который переносится снятая с публикации запись, из параметра `--archive`.
+    pub fn get_archive_path(&self) -> Option<PathBuf> {
+        self.args_map.get("--archive").map(PathBuf::from)
+    }
+
+    /// Сделать из снятой с публикации записи черновик (`--to-draft`).
+    pub fn is_to_draft(&self) -> bool {
+        self.args_map.contains_key("--to-draft")
+    }
+
+    /// Порядок записей из параметра `--sort` (`date` по умолчанию или `title`).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если порядок неизвестен.
+    pub fn get_sort_by(&self) -> Result<SortBy, Error> {
+        match self.args_map.get("--sort").map(String::as_str) {
+            None | Some("date") => Ok(SortBy::Date),
+            Some("title") => Ok(SortBy::Title),
+            Some(unknown) => Err(Error::IncorrectFormat(format!("Unknown sort `{unknown}`, expected date or title"))),
+        }
+    }
+
+    /// Сколько первых значений показывать (`--top`).
+    pub fn get_top(&self) -> Option<usize> {
+        self.args_map.get("--top").and_then(|top| top.trim().parse().ok())
+    }
+
+    /// Искать записи без перевода (`--missing-translations`).
+    pub fn is_missing_translations(&self) -> bool {
+        self.args_map.contains_key("--missing-translations")
+    }
+
+    /// Обратный порядок сортировки (`--reverse`).
+    pub fn is_reverse(&self) -> bool {
+        self.args_map.contains_key("--reverse")
+    }
+
+    /// Номер страницы (`--page`, с 1) и количество записей на странице: `--per-page` или `LIST_PER_PAGE`
+    /// (по умолчанию 20).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если значения не являются целыми числами больше нуля.
+    pub fn get_page(&self) -> Result<(usize, usize), Error> {
+        let page: u64 = match self.args_map.get("--page") {
+            Some(value) => value.trim().parse().ok().filter(|page| *page > 0)
+                .ok_or(Error::IncorrectFormat(format!("--page should be a number starting from 1, got `{value}`")))?,
+            None => 1,
+        };
+        let per_page = self.get_number("--per-page", "LIST_PER_PAGE", DEFAULT_PER_PAGE)?;
+        if per_page == 0 {
+            return Err(Error::IncorrectFormat(String::from("--per-page or LIST_PER_PAGE should be greater than 0")));
+        }
+        Ok((usize::try_from(page).unwrap_or(usize::MAX), usize::try_from(per_page).unwrap_or(usize::MAX)))
+    }
+
+    /// Возвращает путь до манифеста предыдущей выгрузки из параметра `--manifest`.
+    pub fn get_manifest_path(&self) -> Option<PathBuf> {
+        self.args_map.get("--manifest").map(PathBuf::from)
+    }
+
+    /// Возвращает путь из параметра `arg_key`, а если он не задан, то из переменной окружения или профиля `env_key`.
+    fn get_path(&self, arg_key: &str, env_key: &str) -> Result<PathBuf, Error> {
+        if let Some(path) = self.args_map.get(arg_key) {
+            return Ok(PathBuf::from(path));
+        }
+
+        let default_path = self.get_var(env_key).ok_or(Error::EnvVar(env_key.to_string()))?;
+        Ok(PathBuf::from(default_path))
+    }
+
+    /// Флаг включен, если задан параметр `arg_key` или переменная окружения `env_key` равна `true`.
+    fn get_flag(&self, arg_key: &str, env_key: &str) -> bool {
+        self.args_map.contains_key(arg_key)
+            || self.get_var(env_key).is_some_and(|v| v == "true")
+    }
+
+    /// Возвращает список значений через запятую из параметра `arg_key` или переменной окружения `env_key`.
+    fn get_list(&self, arg_key: &str, env_key: &str) -> Vec<String> {
+        let value = self.args_map.get(arg_key).cloned()
+            .or(self.get_var(env_key))
+            .unwrap_or_default();
+
+        value.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect()
+    }
+
+    /// Возвращает пары `key=value`, разделенные `;`, из параметра `arg_key` или переменной окружения `env_key`.
+    fn get_pairs(&self, arg_key: &str, env_key: &str) -> Result<Vec<(String, String)>, Error> {
+        let value = self.args_map.get(arg_key).cloned()
+            .or(self.get_var(env_key))
+            .unwrap_or_default();
+
+        value.split(';')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| rule.split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .ok_or(Error::IncorrectFormat(format!("{env_key} rule should be key=value, got `{rule}`"))))
+            .collect()
+    }
+
+    /// Возвращает дату в формате `2022-01-31` из параметра `arg_key`.
+    fn get_date(&self, arg_key: &str) -> Result<Option<NaiveDate>, Error> {
+        self.args_map.get(arg_key)
+            .map(|value| NaiveDate::parse_from_str(value, ISO8601_DATE_FORMAT).map_err(|_| Error::IncorrectFormat(
+                format!("{arg_key} should be a date like 2022-01-31, got `{value}`")
+            )))
+            .transpose()
+    }
+
+    /// Возвращает число из параметра `arg_key`, переменной окружения `env_key` или `default`.
+    fn get_number(&self, arg_key: &str, env_key: &str, default: u64) -> Result<u64, Error> {
+        let value = match self.args_map.get(arg_key) {
+            Some(v) => v.clone(),
+            None => match self.get_var(env_key) {
+                Some(v) => v,
+                None => return Ok(default),
+            },
+        };
+
+        value.trim().parse().map_err(|_| Error::IncorrectFormat(
+            format!("{arg_key} ({env_key}) should be a non negative number, got `{value}`")
+        ))
+    }
+}
+
+/// Возвращает каталог кэша mashinka: `$XDG_CACHE_HOME/mashinka` или `$HOME/.cache/mashinka`.
+///
+/// # Errors
+///
+/// Вернет `Error` если не задана ни `XDG_CACHE_HOME`, ни `HOME`.
+pub fn cache_dir() -> Result<PathBuf, Error> {
+    let base = match env::var("XDG_CACHE_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var("HOME").map_err(|_| Error::EnvVar(String::from("HOME")))?).join(".cache"),
+    };
+
+    Ok(base.join("mashinka"))
 }
@@ -0,0 +1,229 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::command::Error;
+use crate::config::secrets::SECRET_COMMAND_SUFFIX;
+use crate::config::settings::{settings, SettingKind};
+
+/// Файл настроек в текущем каталоге. Если его нет, используется файл в каталоге настроек пользователя.
+pub const LOCAL_CONFIG_FILE_NAME: &str = "mashinka.toml";
+/// Имя профиля, если не задан `--profile`.
+pub const PROFILE_ENV: &str = "MASHINKA_PROFILE";
+/// Ключ файла настроек с именем профиля по умолчанию.
+const DEFAULT_PROFILE_KEY: &str = "default";
+const PROFILES_KEY: &str = "profiles";
+
+/// Ключи профиля и переменные окружения, которые они заменяют.
+const PROFILE_KEYS: [(&str, &str); 8] = [
+    ("blog_path", "ABS_BASE_PATH_TO_BLOG"),
+    ("draft_path", "ABS_POST_DRAFT_FILE"),
+    ("posts_path", "ABS_POSTS_PATH"),
+    ("translations_path", "ABS_TRANSLATIONS_PATH"),
+    ("index_path", "ABS_INDEX_FILE"),
+    ("build_path", "ABS_BUILD_PATH"),
+    ("build_command", "BUILD_COMMAND"),
+    ("languages", "BLOG_LANGUAGES"),
+];
+/// Разделы профиля: ключ `key` раздела `deploy` заменяет переменную `DEPLOY_KEY`.
+const PROFILE_SECTIONS: [&str; 4] = ["deploy", "compress", "serve", "redirects"];
+
+/// Профиль сайта из файла настроек. Значения хранятся под именами переменных окружения, которые они заменяют,
+/// поэтому `Config` ищет значение сначала в параметрах, потом в переменных окружения и только потом в профиле.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub path: PathBuf,
+    vars: HashMap<String, String>,
+}
+
+impl Profile {
+    /// Загружает профиль `name` (или `MASHINKA_PROFILE`, или профиль по умолчанию из файла) из файла настроек.
+    /// Вернет `None` если файла настроек нет или в нем не выбран профиль.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не разбирается, профиля нет или профиль задан, а файла настроек нет.
+    pub fn load(name: Option<&str>) -> Result<Option<Self>, Error> {
+        let name = name.map(ToString::to_string)
+            .or(env::var(PROFILE_ENV).ok().filter(|name| !name.is_empty()));
+
+        let Some(path) = config_file_path() else {
+            return match name {
+                Some(name) => Err(Error::IncorrectFormat(format!(
+                    "Profile `{name}` is set, but neither ./{LOCAL_CONFIG_FILE_NAME} nor {} exists",
+                    user_config_file_path().map(|p| p.display().to_string()).unwrap_or_default()
+                ))),
+                None => Ok(None),
+            };
+        };
+
+        let content = fs::read_to_string(&path).map_err(Error::ReadFile)?;
+        Self::parse(&content, name.as_deref(), &path)
+    }
+
+    /// Разбирает файл настроек `path` с содержимым `content` и возвращает профиль `name`
+    /// или профиль по умолчанию.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::IncorrectFormat` если файл не разбирается, профиля нет или в нем неизвестные ключи.
+    pub fn parse(content: &str, name: Option<&str>, path: &Path) -> Result<Option<Self>, Error> {
+        let file = path.display();
+        let table: toml::Table = content.parse()
+            .map_err(|e| Error::IncorrectFormat(format!("{file}: {e}")))?;
+
+        let default = table.get(DEFAULT_PROFILE_KEY).and_then(toml::Value::as_str);
+        let Some(name) = name.or(default) else {
+            return Ok(None);
+        };
+
+        let profiles = table.get(PROFILES_KEY).and_then(toml::Value::as_table);
+        let Some(profile) = profiles.and_then(|profiles| profiles.get(name)).and_then(toml::Value::as_table) else {
+            let available: Vec<&str> = profiles.map(|p| p.keys().map(String::as_str).collect()).unwrap_or_default();
+            return Err(Error::IncorrectFormat(format!(
+                "{file}: no profile `{name}`, available: {}", available.join(", ")
+            )));
+        };
+
+        let mut vars = HashMap::new();
+        for (key, value) in profile {
+            if let Some(section) = value.as_table() {
+                if !PROFILE_SECTIONS.contains(&key.as_str()) {
+                    return Err(Error::IncorrectFormat(format!(
+                        "{file}: unknown section `{key}` in profile `{name}`, expected one of {}", PROFILE_SECTIONS.join(", ")
+                    )));
+                }
+
+                let known = section_keys(key);
+                for (section_key, value) in section {
+                    if !known.contains(section_key) {
+                        return Err(Error::IncorrectFormat(format!(
+                            "{file}: unknown key `{section_key}` in section `{key}` of profile `{name}`, expected one of {}",
+                            known.join(", ")
+                        )));
+                    }
+
+                    let var = format!("{}_{}", key.to_uppercase(), section_key.to_uppercase());
+                    vars.insert(var, to_env_value(value, &file, section_key)?);
+                }
+                continue;
+            }
+
+            let Some((_, var)) = PROFILE_KEYS.iter().find(|(profile_key, _)| profile_key == key) else {
+                let keys: Vec<&str> = PROFILE_KEYS.iter().map(|(k, _)| *k).collect();
+                return Err(Error::IncorrectFormat(format!(
+                    "{file}: unknown key `{key}` in profile `{name}`, expected one of {}", keys.join(", ")
+                )));
+            };
+            vars.insert((*var).to_string(), to_env_value(value, &file, key)?);
+        }
+
+        Ok(Some(Self { name: name.to_string(), path: path.to_path_buf(), vars }))
+    }
+
+    /// Значение, которое заменяет переменную окружения `env_key`.
+    pub fn get(&self, env_key: &str) -> Option<&String> {
+        self.vars.get(env_key)
+    }
+}
+
+/// Ключи раздела `section`: переменные `SECTION_*` из таблицы настроек без префикса, для реквизитов выгрузки
+/// еще и `*_command`.
+fn section_keys(section: &str) -> Vec<String> {
+    let prefix = format!("{}_", section.to_uppercase());
+    let mut keys = Vec::new();
+    for setting in settings() {
+        let Some(key) = setting.env.strip_prefix(&prefix) else {
+            continue;
+        };
+        keys.push(key.to_lowercase());
+        if matches!(setting.kind, SettingKind::Credential | SettingKind::Secret) {
+            keys.push(format!("{key}{SECRET_COMMAND_SUFFIX}").to_lowercase());
+        }
+    }
+    keys
+}
+
+/// Преобразует значение toml в строку в формате переменной окружения: списки через запятую.
+fn to_env_value(value: &toml::Value, file: &impl std::fmt::Display, key: &str) -> Result<String, Error> {
+    match value {
+        toml::Value::String(value) => Ok(value.clone()),
+        toml::Value::Integer(value) => Ok(value.to_string()),
+        toml::Value::Boolean(value) => Ok(value.to_string()),
+        toml::Value::Array(values) => values.iter()
+            .map(|value| to_env_value(value, file, key))
+            .collect::<Result<Vec<String>, Error>>()
+            .map(|values| values.join(",")),
+        _ => Err(Error::IncorrectFormat(format!("{file}: unsupported value for `{key}`"))),
+    }
+}
+
+/// Путь до файла настроек: `./mashinka.toml`, если он есть, иначе `~/.config/mashinka/config.toml`, если он есть.
+pub fn config_file_path() -> Option<PathBuf> {
+    let local = PathBuf::from(LOCAL_CONFIG_FILE_NAME);
+    if local.is_file() {
+        return Some(local);
+    }
+
+    user_config_file_path().filter(|path| path.is_file())
+}
+
+/// `$XDG_CONFIG_HOME/mashinka/config.toml` или `$HOME/.config/mashinka/config.toml`.
+fn user_config_file_path() -> Option<PathBuf> {
+    let base = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var("HOME").ok()?).join(".config"),
+    };
+
+    Some(base.join("mashinka").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::config::profile::Profile;
+
+    const CONFIG: &str = r#"
+default = "personal"
+
+[profiles.personal]
+posts_path = "/blog/content/posts"
+languages = ["ru", "en"]
+
+[profiles.team]
+build_path = "/team/build"
+
+[profiles.team.deploy]
+container = "team-blog"
+retries = 5
+per_file = true
+"#;
+
+    #[test]
+    fn test_parse_profile() {
+        let path = Path::new("mashinka.toml");
+
+        let personal = Profile::parse(CONFIG, None, path).unwrap().unwrap();
+        assert_eq!("personal", personal.name);
+        assert_eq!(Some(&"/blog/content/posts".to_string()), personal.get("ABS_POSTS_PATH"));
+        assert_eq!(Some(&"ru,en".to_string()), personal.get("BLOG_LANGUAGES"));
+
+        let team = Profile::parse(CONFIG, Some("team"), path).unwrap().unwrap();
+        assert_eq!(Some(&"team-blog".to_string()), team.get("DEPLOY_CONTAINER"));
+        assert_eq!(Some(&"5".to_string()), team.get("DEPLOY_RETRIES"));
+        assert_eq!(Some(&"true".to_string()), team.get("DEPLOY_PER_FILE"));
+        assert_eq!(None, team.get("ABS_POSTS_PATH"));
+
+        assert!(Profile::parse(CONFIG, Some("work"), path).is_err());
+        assert!(Profile::parse("[profiles.x]\npost_path = \"/p\"", Some("x"), path).is_err());
+        assert!(Profile::parse("[profiles.x.deploy]\nretry_delay = 100", Some("x"), path).is_err());
+        let x = Profile::parse("[profiles.x.deploy]\nretry_delay_ms = 100\npassword_command = \"pass\"", Some("x"), path)
+            .unwrap().unwrap();
+        assert_eq!(Some(&"100".to_string()), x.get("DEPLOY_RETRY_DELAY_MS"));
+        assert_eq!(Some(&"pass".to_string()), x.get("DEPLOY_PASSWORD_COMMAND"));
+        assert_eq!(None, Profile::parse("[profiles.x]", None, path).unwrap());
+    }
+}
@@ -0,0 +1,151 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process;
+use age::secrecy::Secret;
+use crate::command::Error;
+
+/// Суффикс переменной окружения с командой, которая выводит значение секрета.
+pub const SECRET_COMMAND_SUFFIX: &str = "_COMMAND";
+/// Путь к зашифрованному age (по паролю) файлу с секретами в формате `.env`.
+pub const SECRETS_FILE_ENV: &str = "DEPLOY_SECRETS_FILE";
+/// Пароль от файла с секретами. Его тоже можно получить командой через `DEPLOY_SECRETS_PASSPHRASE_COMMAND`.
+pub const SECRETS_PASSPHRASE_ENV: &str = "DEPLOY_SECRETS_PASSPHRASE";
+
+/// Источник секретов. Значение `NAME` ищется по порядку:
+/// в переменной `NAME`, в выводе команды из `NAME_COMMAND`, в файле `DEPLOY_SECRETS_FILE`.
+/// Переменные берутся через `lookup`, чтобы учитывать профиль из файла настроек.
+/// Файл расшифровывается один раз и только если значение не нашлось раньше.
+pub struct Secrets<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    file: OnceCell<HashMap<String, String>>,
+}
+
+impl<'a> Secrets<'a> {
+    pub fn new(lookup: &'a dyn Fn(&str) -> Option<String>) -> Self {
+        Self { lookup, file: OnceCell::new() }
+    }
+
+    /// Возвращает значение секрета `name`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::EnvVar` если секрет нигде не задан и `Error::Secret` если не удалось выполнить команду
+    /// или расшифровать файл.
+    pub fn get(&self, name: &str) -> Result<String, Error> {
+        if let Some(value) = self.lookup_or_command(name)? {
+            return Ok(value);
+        }
+
+        let Some(secrets_file) = (self.lookup)(SECRETS_FILE_ENV) else {
+            return Err(Error::EnvVar(name.to_string()));
+        };
+
+        if self.file.get().is_none() {
+            let passphrase = self.lookup_or_command(SECRETS_PASSPHRASE_ENV)?;
+            let _ = self.file.set(read_secrets_file(Path::new(&secrets_file), passphrase)?);
+        }
+
+        match self.file.get().and_then(|secrets| secrets.get(name)) {
+            Some(value) => Ok(value.clone()),
+            None => Err(Error::EnvVar(name.to_string())),
+        }
+    }
+
+    /// Значение переменной `name` или вывод команды из `name_COMMAND`.
+    fn lookup_or_command(&self, name: &str) -> Result<Option<String>, Error> {
+        if let Some(value) = (self.lookup)(name) {
+            return Ok(Some(value));
+        }
+
+        match (self.lookup)(&format!("{name}{SECRET_COMMAND_SUFFIX}")) {
+            Some(command) if !command.trim().is_empty() => run_command(name, &command).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Выполняет `command` через `sh -c` и возвращает первую строку вывода.
+/// Вывод команды не попадает в текст ошибки, чтобы не раскрыть секрет.
+fn run_command(name: &str, command: &str) -> Result<String, Error> {
+    let output = process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(process::Stdio::inherit())
+        .stderr(process::Stdio::inherit())
+        .output()
+        .map_err(|e| Error::Secret(format!("{name}{SECRET_COMMAND_SUFFIX}: {e}")))?;
+
+    if !output.status.success() {
+        return Err(Error::Secret(format!("{name}{SECRET_COMMAND_SUFFIX} exited with {}", output.status)));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| Error::Secret(format!("{name}{SECRET_COMMAND_SUFFIX} printed non UTF-8 value")))?;
+
+    stdout.lines().next()
+        .map(|line| line.trim_end().to_string())
+        .filter(|line| !line.is_empty())
+        .ok_or(Error::Secret(format!("{name}{SECRET_COMMAND_SUFFIX} printed nothing")))
+}
+
+/// Расшифровывает файл с секретами паролем из `DEPLOY_SECRETS_PASSPHRASE`.
+fn read_secrets_file(path: &Path, passphrase: Option<String>) -> Result<HashMap<String, String>, Error> {
+    let passphrase = passphrase.ok_or(Error::Secret(
+        format!("{SECRETS_PASSPHRASE_ENV} or {SECRETS_PASSPHRASE_ENV}{SECRET_COMMAND_SUFFIX} should be set to read {}", path.display())
+    ))?;
+
+    let file = File::open(path).map_err(Error::ReadFile)?;
+    let decryptor = match age::Decryptor::new(file) {
+        Ok(age::Decryptor::Passphrase(decryptor)) => decryptor,
+        Ok(_) => return Err(Error::Secret(format!("{} should be encrypted with a passphrase", path.display()))),
+        Err(e) => return Err(Error::Secret(format!("{}: {e}", path.display()))),
+    };
+
+    let mut content = String::new();
+    decryptor.decrypt(&Secret::new(passphrase), None)
+        .map_err(|e| Error::Secret(format!("{}: {e}", path.display())))?
+        .read_to_string(&mut content)
+        .map_err(Error::ReadFile)?;
+
+    Ok(parse_secrets(&content))
+}
+
+/// Разбирает содержимое в формате `.env`: строки `KEY=value`, пустые строки и комментарии `#` пропускаются.
+fn parse_secrets(content: &str) -> HashMap<String, String> {
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let unquoted = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+            (key.trim().to_string(), unquoted.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::secrets::{parse_secrets, run_command};
+
+    #[test]
+    fn test_parse_secrets() {
+        let secrets = parse_secrets("# selectel\nDEPLOY_PASSWORD=\"p@ss=word\"\n\nDEPLOY_USERNAME = user\n");
+
+        assert_eq!(Some(&"p@ss=word".to_string()), secrets.get("DEPLOY_PASSWORD"));
+        assert_eq!(Some(&"user".to_string()), secrets.get("DEPLOY_USERNAME"));
+        assert_eq!(2, secrets.len());
+    }
+
+    #[test]
+    fn test_run_command_returns_first_line() {
+        assert_eq!("secret", run_command("TEST", "printf 'secret\\nother'").unwrap());
+        assert!(run_command("TEST", "exit 1").is_err());
+        assert!(run_command("TEST", "true").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::config::{Config, DEFAULT_EDITOR, DEFAULT_PER_PAGE, DEFAULT_HISTORY_SIZE, DEFAULT_RETRY_ATTEMPTS, DEFAULT_RETRY_DELAY_MS, DEFAULT_SERVE_PORT};
+use crate::config::profile::PROFILE_ENV;
+use crate::config::secrets::{SECRET_COMMAND_SUFFIX, SECRETS_FILE_ENV, SECRETS_PASSPHRASE_ENV};
+use crate::deploy::{DEFAULT_COMPRESS_MIN_SIZE, DEFAULT_LOCK_TTL_SECS, DEFAULT_PRUNE_MAX_PERCENT, DEFAULT_VERIFY_PATHS};
+
+/// Маска, которой заменяются значения секретов при выводе.
+pub const SECRET_MASK: &str = "******";
+
+/// Что хранится в настройке, от этого зависит проверка `config check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    /// Произвольная строка.
+    Text,
+    /// Целое неотрицательное число.
+    Number,
+    /// Флаг `true`/`false`.
+    Switch,
+    /// Существующий каталог.
+    Dir,
+    /// Существующий каталог, в который можно писать.
+    WritableDir,
+    /// Файл или каталог, который должен существовать.
+    Existing,
+    /// Файл, в который можно писать. Если файла нет, то можно писать в его каталог.
+    WritableFile,
+    /// Реквизит выгрузки: может быть задан через `*_COMMAND` или `DEPLOY_SECRETS_FILE`.
+    Credential,
+    /// Секрет: значение не выводится.
+    Secret,
+}
+
+/// Настройка: переменная окружения (она же ключ профиля), параметр командной строки и значение по умолчанию.
+/// Значение по умолчанию берется у тех же методов `Config`, которыми пользуются команды, поэтому
+/// `config show` показывает то, что будет использовано на самом деле.
+pub struct Setting {
+    pub env: &'static str,
+    pub flag: Option<&'static str>,
+    pub kind: SettingKind,
+    /// Настройка, значение которой используется, если эта не задана.
+    fallback: Option<&'static str>,
+    default: fn(&Config) -> Option<String>,
+}
+
+/// Откуда взято значение настройки.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Flag(&'static str),
+    Env,
+    /// Профиль `name` из файла `path`.
+    File(String, PathBuf),
+    /// Команда `*_COMMAND`, которая выведет значение.
+    Command(String),
+    /// Зашифрованный файл секретов.
+    SecretsFile(String),
+    /// Значение другой настройки `env` из ее источника.
+    Setting(&'static str, Box<Source>),
+    Default,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Flag(flag) => write!(f, "flag {flag}"),
+            Source::Env => write!(f, "env"),
+            Source::File(name, path) => write!(f, "file {} [{name}]", path.display()),
+            Source::Command(env_key) => write!(f, "command {env_key}"),
+            Source::SecretsFile(path) => write!(f, "secrets file {path}"),
+            Source::Setting(env_key, source) => write!(f, "same as {env_key}, {source}"),
+            Source::Default => write!(f, "default"),
+        }
+    }
+}
+
+impl Setting {
+    const fn new(env: &'static str, flag: Option<&'static str>, kind: SettingKind) -> Self {
+        Self { env, flag, kind, fallback: None, default: |_| None }
+    }
+
+    const fn with_default(mut self, default: fn(&Config) -> Option<String>) -> Self {
+        self.default = default;
+        self
+    }
+
+    const fn with_fallback(mut self, env: &'static str) -> Self {
+        self.fallback = Some(env);
+        self
+    }
+
+    pub fn is_secret(&self) -> bool {
+        self.kind == SettingKind::Secret
+    }
+
+    pub fn is_path(&self) -> bool {
+        matches!(self.kind, SettingKind::Dir | SettingKind::WritableDir | SettingKind::Existing | SettingKind::WritableFile)
+    }
+}
+
+/// Все настройки mashinka в порядке вывода `config show`.
+pub fn settings() -> Vec<Setting> {
+    use SettingKind::{Credential, Dir, Existing, Number, Secret, Switch, Text, WritableDir, WritableFile};
+
+    vec![
+        Setting::new(PROFILE_ENV, Some("--profile"), Text),
+        Setting::new("ABS_BASE_PATH_TO_BLOG", Some("--blog-path"), Dir),
+        Setting::new("ABS_POST_DRAFT_FILE", Some("--draft-path"), Existing),
+        Setting::new("ABS_POSTS_PATH", Some("--posts-path"), WritableDir),
+        Setting::new("ABS_TRANSLATIONS_PATH", Some("--translations-path"), WritableDir),
+        Setting::new("ABS_INDEX_FILE", Some("--index-path"), WritableFile),
+        Setting::new("ABS_BUILD_PATH", Some("--build-path"), WritableDir),
+        Setting::new("BUILD_COMMAND", Some("--build-command"), Text)
+            .with_default(|config| Some(config.get_build_command())),
+        Setting::new("REDIRECTS_FILE", Some("--redirects-path"), WritableFile)
+            .with_default(|config| config.get_redirects_path().ok().map(|path| path.display().to_string())),
+        Setting::new("REDIRECTS_FORMAT", Some("--redirects"), Text).with_default(|_| Some(String::from("html"))),
+        Setting::new("BLOG_LANGUAGES", None, Text).with_default(|config| Some(
+            config.available_languages().iter().map(|lang| lang.to_lowercase()).collect::<Vec<_>>().join(",")
+        )),
+        Setting::new("LIST_PER_PAGE", Some("--per-page"), Number).with_default(|_| Some(DEFAULT_PER_PAGE.to_string())),
+        Setting::new("VISUAL", Some("--editor"), Text),
+        Setting::new("EDITOR", None, Text).with_default(|_| Some(DEFAULT_EDITOR.to_string())),
+        Setting::new("DEPLOY_ACCOUNT", None, Credential),
+        Setting::new("DEPLOY_USERNAME", None, Credential),
+        Setting::new("DEPLOY_PASSWORD", None, Secret),
+        Setting::new("DEPLOY_CONTAINER", None, Credential),
+        Setting::new("DEPLOY_DEST", None, Credential),
+        Setting::new(SECRETS_FILE_ENV, None, Existing),
+        Setting::new(SECRETS_PASSPHRASE_ENV, None, Secret),
+        Setting::new("DEPLOY_SITE_URL", Some("--site-url"), Text).with_fallback("DEPLOY_DEST"),
+        Setting::new("DEPLOY_VERIFY_PATHS", Some("--verify-paths"), Text)
+            .with_default(|_| Some(DEFAULT_VERIFY_PATHS.to_string())),
+        Setting::new("DEPLOY_RETRIES", Some("--retries"), Number)
+            .with_default(|_| Some(DEFAULT_RETRY_ATTEMPTS.to_string())),
+        Setting::new("DEPLOY_RETRY_DELAY_MS", Some("--retry-delay"), Number)
+            .with_default(|_| Some(DEFAULT_RETRY_DELAY_MS.to_string())),
+        Setting::new("DEPLOY_PER_FILE", Some("--per-file"), Switch),
+        Setting::new("DEPLOY_EXCLUDE", Some("--exclude"), Text),
+        Setting::new("DEPLOY_INCLUDE", Some("--include"), Text),
+        Setting::new("DEPLOY_MIME_TYPES", Some("--mime-types"), Text),
+        Setting::new("DEPLOY_CACHE_CONTROL", Some("--cache-control"), Text),
+        Setting::new("DEPLOY_COMPRESS", Some("--compress"), Switch),
+        Setting::new("DEPLOY_PRUNE", Some("--prune"), Switch),
+        Setting::new("DEPLOY_PRUNE_MAX_PERCENT", Some("--prune-max-percent"), Number)
+            .with_default(|_| Some(DEFAULT_PRUNE_MAX_PERCENT.to_string())),
+        Setting::new("DEPLOY_LOCK_TTL", Some("--lock-ttl"), Number)
+            .with_default(|_| Some(DEFAULT_LOCK_TTL_SECS.to_string())),
+        Setting::new("DEPLOY_HISTORY_PATH", Some("--history-path"), WritableDir)
+            .with_default(|config| config.get_history_path_or_default().ok().map(|path| path.display().to_string())),
+        Setting::new("DEPLOY_HISTORY_SIZE", Some("--history-size"), Number)
+            .with_default(|_| Some(DEFAULT_HISTORY_SIZE.to_string())),
+        Setting::new("COMPRESS_BROTLI", Some("--brotli"), Switch),
+        Setting::new("COMPRESS_MIN_SIZE", Some("--min-size"), Number)
+            .with_default(|_| Some(DEFAULT_COMPRESS_MIN_SIZE.to_string())),
+        Setting::new("SERVE_PORT", Some("--port"), Number)
+            .with_default(|_| Some(DEFAULT_SERVE_PORT.to_string())),
+    ]
+}
+
+impl Config {
+    /// Значение настройки и его источник в том же порядке, в каком их ищут команды:
+    /// параметр, переменная окружения, профиль, `*_COMMAND` и файл секретов для реквизитов выгрузки,
+    /// значение по умолчанию. Команды `*_COMMAND` не выполняются и не возвращаются, вместо значения `<command output>`.
+    /// Вернет `None` если настройка не задана.
+    pub fn resolve(&self, setting: &Setting) -> Option<(String, Source)> {
+        self.resolve_with(setting, &|key| env::var(key).ok())
+    }
+
+    /// То же, что `resolve`, но переменные окружения берутся через `lookup`.
+    fn resolve_with(&self, setting: &Setting, lookup: &dyn Fn(&str) -> Option<String>) -> Option<(String, Source)> {
+        let env = |key: &str| lookup(key).filter(|value| !value.is_empty());
+        let var = |key: &str| env(key).or(self.profile.as_ref().and_then(|profile| profile.get(key).cloned()));
+
+        if let Some(value) = setting.flag.and_then(|flag| self.args_map.get(flag)) {
+            return Some((value.clone(), Source::Flag(setting.flag.unwrap_or_default())));
+        }
+
+        if let Some(value) = env(setting.env) {
+            return Some((value, Source::Env));
+        }
+
+        if let Some(profile) = &self.profile {
+            if let Some(value) = profile.get(setting.env) {
+                return Some((value.clone(), Source::File(profile.name.clone(), profile.path.clone())));
+            }
+        }
+
+        if matches!(setting.kind, SettingKind::Credential | SettingKind::Secret) {
+            let command_key = format!("{}{SECRET_COMMAND_SUFFIX}", setting.env);
+            // в команде может быть сам секрет (`echo <пароль>`), поэтому выводится только то, что она задана
+            if var(&command_key).is_some() {
+                return Some((String::from("<command output>"), Source::Command(command_key)));
+            }
+
+            if setting.env.starts_with("DEPLOY_") && setting.env != SECRETS_PASSPHRASE_ENV {
+                if let Some(path) = var(SECRETS_FILE_ENV) {
+                    return Some((String::from("<encrypted>"), Source::SecretsFile(path)));
+                }
+            }
+        }
+
+        if let Some(fallback) = setting.fallback.and_then(|env| settings().into_iter().find(|s| s.env == env)) {
+            if let Some((value, source)) = self.resolve_with(&fallback, lookup) {
+                return Some((value, Source::Setting(fallback.env, Box::new(source))));
+            }
+        }
+
+        // профиль по умолчанию выбирается ключом `default` файла настроек
+        if setting.env == PROFILE_ENV {
+            if let Some(profile) = &self.profile {
+                return Some((profile.name.clone(), Source::File(profile.name.clone(), profile.path.clone())));
+            }
+        }
+
+        (setting.default)(self).map(|value| (value, Source::Default))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use crate::config::Config;
+    use crate::config::profile::Profile;
+    use crate::config::settings::{settings, Source};
+
+    #[test]
+    fn test_resolve_source() {
+        let content = "[profiles.blog]\nposts_path = \"/profile/posts\"\n[profiles.blog.deploy]\npassword_command = \"pass show blog\"\n";
+        let path = PathBuf::from("/tmp/mashinka.toml");
+        let profile = Profile::parse(content, Some("blog"), &path).unwrap();
+        let args_map = HashMap::from([(String::from("--build-path"), String::from("/flag/build"))]);
+        let config = Config { args_map, profile };
+
+        // переменные окружения разработчика не должны влиять на тест
+        let env = HashMap::from([
+            ("DEPLOY_USERNAME", String::from("env-user")),
+            ("DEPLOY_DEST", String::from("https://example.com")),
+        ]);
+        let find = |key: &str| {
+            let setting = settings().into_iter().find(|s| s.env == key).unwrap();
+            config.resolve_with(&setting, &|name| env.get(name).cloned())
+        };
+
+        assert_eq!(Some((String::from("/flag/build"), Source::Flag("--build-path"))), find("ABS_BUILD_PATH"));
+        assert_eq!(
+            Some((String::from("/profile/posts"), Source::File(String::from("blog"), path.clone()))),
+            find("ABS_POSTS_PATH")
+        );
+        assert_eq!(
+            Some((String::from("<command output>"), Source::Command(String::from("DEPLOY_PASSWORD_COMMAND")))),
+            find("DEPLOY_PASSWORD")
+        );
+        assert_eq!(Some((String::from("env-user"), Source::Env)), find("DEPLOY_USERNAME"));
+        assert_eq!(
+            Some((String::from("https://example.com"), Source::Setting("DEPLOY_DEST", Box::new(Source::Env)))),
+            find("DEPLOY_SITE_URL")
+        );
+        assert_eq!(Some((String::from("1024"), Source::Default)), find("COMPRESS_MIN_SIZE"));
+    }
+}
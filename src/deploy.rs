@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+
+pub mod archive;
+pub mod compress;
+pub mod filter;
+pub mod history;
+pub mod lock;
+pub mod manifest;
+pub mod metadata;
+pub mod progress;
+pub mod prune;
+pub mod report;
+pub mod retry;
+pub mod selectel;
+pub mod token;
+pub mod upload;
+pub mod verify;
+
+pub const SELECTEL_AUTH_URL: &str = "https://api.selcdn.ru/auth/v1.0";
+pub const SELECTEL_API_URL: &str = "https://api.selcdn.ru/v1";
+
+pub const ARCHIVE_FILE_NAME: &str = "build.tar.gz";
+/// Каталог в каталоге кеша, в котором хранятся журналы выгрузки по файлам.
+pub const UPLOAD_JOURNALS_DIR_NAME: &str = "uploads";
+/// Файл в каталоге кеша, в котором хранится токен API хранилища.
+pub const TOKEN_FILE_NAME: &str = "token";
+/// Файл с правилами исключения файлов сборки из выгрузки в формате gitignore.
+pub const IGNORE_FILE_NAME: &str = ".mashinkaignore";
+
+/// Максимальная задержка между повторами запросов.
+pub const MAX_RETRY_DELAY_SECS: u64 = 30;
+
+/// Минимальный размер файла в байтах, для которого создаются сжатые копии.
+pub const DEFAULT_COMPRESS_MIN_SIZE: u64 = 1024;
+
+/// Сколько процентов объектов хранилища можно удалить при `--prune` без `--force`.
+pub const DEFAULT_PRUNE_MAX_PERCENT: u64 = 20;
+/// Сколько объектов запрашивать за раз при получении списка объектов контейнера.
+pub const LIST_OBJECTS_LIMIT: usize = 10000;
+/// Через сколько секунд блокировка выгрузки считается брошенной.
+pub const DEFAULT_LOCK_TTL_SECS: u64 = 1800;
+
+/// Псевдоним для адреса самой новой записи в списке проверяемых после выгрузки адресов.
+pub const NEWEST_POST_ALIAS: &str = "@newest";
+/// Псевдоним для адреса файла индекса `ABS_INDEX_FILE` в списке проверяемых после выгрузки адресов.
+pub const INDEX_FILE_ALIAS: &str = "@index";
+pub const DEFAULT_VERIFY_PATHS: &str = "/,@newest,@index";
@@ -0,0 +1,129 @@
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use crate::command::Error;
+use crate::deploy::filter::BuildFilter;
+use crate::deploy::progress::{Progress, ProgressReader};
+
+/// Создает tar.gz из файлов `files` директории `dir_path` (пути относительно `dir_path`),
+/// показывая прогресс по прочитанным байтам.
+///
+/// # Errors
+///
+/// Вернет `Error` если не удалось создать файл архива или прочитать файлы.
+pub fn encode_dir(archive_path: &Path, dir_path: &Path, files: &[PathBuf]) -> Result<(), Error> {
+    let tar_gz = File::create(archive_path).map_err(Error::CreateFile)?;
+    let enc = GzEncoder::new(tar_gz, Compression::best());
+    let mut tar = tar::Builder::new(enc);
+
+    let total = files.iter()
+        .map(|f| dir_path.join(f).metadata().map(|m| m.len()).unwrap_or(0))
+        .sum();
+    let progress = Progress::new("archive", total);
+
+    for relative_path in files {
+        let path = dir_path.join(relative_path);
+        let file = File::open(&path).map_err(Error::ReadFile)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata(&file.metadata().map_err(Error::ReadFile)?);
+
+        tar.append_data(&mut header, relative_path, ProgressReader::new(file, &progress))
+            .map_err(Error::CreateArchive)?;
+    }
+
+    tar.into_inner().map_err(Error::CreateArchive)?
+        .finish().map_err(Error::CreateArchive)?;
+    progress.finish();
+    Ok(())
+}
+
+/// Распаковывает tar.gz `archive_path` в директорию `dir_path` и возвращает отсортированный список файлов
+/// с путями относительно `dir_path`. Записи с путями вне `dir_path` пропускаются.
+///
+/// # Errors
+///
+/// Вернет `Error` если не удалось прочитать архив или записать файлы.
+pub fn decode_archive(archive_path: &Path, dir_path: &Path) -> Result<Vec<PathBuf>, Error> {
+    fs::create_dir_all(dir_path).map_err(Error::CreateFile)?;
+    let tar_gz = File::open(archive_path).map_err(Error::ReadFile)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(tar_gz));
+
+    let mut files = Vec::new();
+    for entry in archive.entries().map_err(Error::ReadFile)? {
+        let mut entry = entry.map_err(Error::ReadFile)?;
+        let relative = entry.path().map_err(Error::ReadFile)?.to_path_buf();
+        let is_file = entry.header().entry_type().is_file();
+
+        if entry.unpack_in(dir_path).map_err(Error::WriteFile)? && is_file {
+            files.push(relative);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Возвращает отсортированный список файлов директории `dir_path` (рекурсивно) с путями
+/// относительно `dir_path`. Файлы и директории, исключенные `filter`, пропускаются.
+///
+/// # Errors
+///
+/// Вернет `Error` если не удалось прочитать директорию.
+pub fn collect_files(dir_path: &Path, filter: &BuildFilter) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir_path.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).map_err(Error::ReadDir)? {
+            let path = entry.map_err(Error::ReadDir)?.path();
+            let Ok(relative) = path.strip_prefix(dir_path) else { continue };
+            let is_dir = path.is_dir();
+
+            if filter.is_excluded(relative, is_dir) {
+                continue;
+            }
+
+            if is_dir {
+                dirs.push(path);
+            } else {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Преобразует относительный путь файла в ключ объекта хранилища (разделитель `/`).
+pub fn object_key(relative_path: &Path) -> String {
+    relative_path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use crate::deploy::archive::{decode_archive, encode_dir};
+
+    #[test]
+    fn test_decode_encoded_dir() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("build/index.html").write_str("<html></html>").unwrap();
+        dir.child("build/ru/posts/a/index.html").write_str("<html>a</html>").unwrap();
+        let files = vec![PathBuf::from("index.html"), PathBuf::from("ru/posts/a/index.html")];
+
+        let archive = dir.path().join("build.tar.gz");
+        encode_dir(&archive, dir.child("build").path(), &files).unwrap();
+
+        let unpacked = dir.path().join("unpacked");
+        assert_eq!(files, decode_archive(&archive, &unpacked).unwrap());
+        assert_eq!("<html>a</html>", std::fs::read_to_string(unpacked.join("ru/posts/a/index.html")).unwrap());
+    }
+}
@@ -0,0 +1,133 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use crate::command::Error;
+
+/// Расширения файлов, для которых создаются сжатые копии.
+pub const COMPRESSIBLE_EXTENSIONS: [&str; 5] = ["html", "css", "js", "json", "svg"];
+
+const BROTLI_BUFFER_SIZE: usize = 4096;
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+
+/// Итог создания сжатых копий.
+#[derive(Debug, Default)]
+pub struct CompressSummary {
+    /// Созданные или обновленные копии.
+    pub written: Vec<PathBuf>,
+    /// Копии, которые не изменились с прошлого запуска.
+    pub skipped: usize,
+}
+
+/// Создает рядом с файлами сборки сжатые копии `.gz` (и `.br`, если `brotli`) для html, css, js,
+/// json и svg файлов размером от `min_size` байт. Копия не пересоздается, если она новее оригинала.
+/// С `dry_run` только возвращает список копий, которые будут созданы.
+///
+/// # Errors
+///
+/// Вернет `Error` если не удалось прочитать оригинал или записать копию.
+pub fn compress_files(build_path: &Path, files: &[PathBuf], min_size: u64, brotli: bool, dry_run: bool) -> Result<CompressSummary, Error> {
+    let mut summary = CompressSummary::default();
+    let encodings: &[&str] = if brotli { &["gz", "br"] } else { &["gz"] };
+
+    for relative_path in files.iter().filter(|f| is_compressible(f)) {
+        let source = build_path.join(relative_path);
+        let metadata = source.metadata().map_err(Error::ReadFile)?;
+
+        if metadata.len() < min_size {
+            continue;
+        }
+
+        for encoding in encodings {
+            let target = sibling(&source, encoding);
+
+            if is_up_to_date(&source, &target) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            if !dry_run {
+                compress_file(&source, &target, encoding)?;
+            }
+
+            summary.written.push(sibling(relative_path, encoding));
+        }
+    }
+
+    Ok(summary)
+}
+
+fn is_compressible(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| COMPRESSIBLE_EXTENSIONS.contains(&e.as_str()))
+}
+
+fn sibling(path: &Path, encoding: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{encoding}"));
+    PathBuf::from(name)
+}
+
+fn is_up_to_date(source: &Path, target: &Path) -> bool {
+    let modified = |p: &Path| p.metadata().and_then(|m| m.modified()).ok();
+
+    match (modified(source), modified(target)) {
+        (Some(source), Some(target)) => target >= source,
+        _ => false,
+    }
+}
+
+/// Сжимает `source` во временный файл рядом с `target` и переименовывает его в `target`. Прерванное сжатие
+/// не оставляет обрезанную копию, которая новее оригинала и поэтому больше не пересоздавалась бы.
+fn compress_file(source: &Path, target: &Path, encoding: &str) -> Result<(), Error> {
+    let temp = sibling(target, "tmp");
+    let result = write_compressed(source, &temp, encoding)
+        .and_then(|()| fs::rename(&temp, target).map_err(Error::WriteFile));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_compressed(source: &Path, target: &Path, encoding: &str) -> Result<(), Error> {
+    let mut input = File::open(source).map_err(Error::ReadFile)?;
+    let output = File::create(target).map_err(Error::CreateFile)?;
+
+    if encoding == "br" {
+        let mut encoder = brotli::CompressorWriter::new(output, BROTLI_BUFFER_SIZE, BROTLI_QUALITY, BROTLI_WINDOW);
+        io::copy(&mut input, &mut encoder).map_err(Error::WriteFile)?;
+        encoder.flush().map_err(Error::WriteFile)?;
+        encoder.into_inner();
+    } else {
+        let mut encoder = GzEncoder::new(output, Compression::best());
+        io::copy(&mut input, &mut encoder).map_err(Error::WriteFile)?;
+        encoder.finish().map_err(Error::WriteFile)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deploy::compress::compress_file;
+
+    #[test]
+    fn test_failed_compression_leaves_no_copy() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let source = dir.path().join("index.html");
+        let target = dir.path().join("index.html.gz");
+
+        assert!(compress_file(&source, &target, "gz").is_err());
+        assert!(!target.exists() && !dir.path().join("index.html.gz.tmp").exists());
+
+        std::fs::write(&source, "<p>text</p>").unwrap();
+        compress_file(&source, &target, "gz").unwrap();
+        assert!(target.exists() && !dir.path().join("index.html.gz.tmp").exists());
+    }
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::path::Path;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use crate::command::Error;
+use crate::deploy::IGNORE_FILE_NAME;
+
+/// Фильтр файлов сборки по правилам в формате gitignore. Правила собираются в порядке:
+/// исключения из конфигурации, файл `.mashinkaignore` в директории сборки, включения из
+/// конфигурации (как `!pattern`), поэтому включения имеют приоритет.
+pub struct BuildFilter {
+    gitignore: Gitignore,
+}
+
+impl BuildFilter {
+    /// # Errors
+    ///
+    /// Вернет `Error` если какое-либо правило или файл `.mashinkaignore` имеет неверный формат.
+    pub fn new(build_path: &Path, exclude: &[String], include: &[String]) -> Result<Self, Error> {
+        let mut builder = GitignoreBuilder::new(build_path);
+        let incorrect = |e: ignore::Error| Error::IncorrectFormat(format!("Exclude pattern: {e}"));
+
+        builder.add_line(None, IGNORE_FILE_NAME).map_err(incorrect)?;
+
+        for pattern in exclude {
+            builder.add_line(None, pattern).map_err(incorrect)?;
+        }
+
+        let ignore_file = build_path.join(IGNORE_FILE_NAME);
+        if ignore_file.exists() {
+            if let Some(e) = builder.add(ignore_file) {
+                return Err(incorrect(e));
+            }
+        }
+
+        for pattern in include {
+            builder.add_line(None, &format!("!{pattern}")).map_err(incorrect)?;
+        }
+
+        Ok(Self { gitignore: builder.build().map_err(incorrect)? })
+    }
+
+    /// Фильтр без правил, пропускает все файлы.
+    pub fn empty() -> Self {
+        Self { gitignore: Gitignore::empty() }
+    }
+
+    /// Проверяет, исключен ли файл или директория `relative_path` (путь относительно директории сборки).
+    pub fn is_excluded(&self, relative_path: &Path, is_dir: bool) -> bool {
+        self.gitignore.matched(relative_path, is_dir).is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::deploy::filter::BuildFilter;
+
+    #[test]
+    fn test_exclude_and_include_patterns() {
+        let exclude = vec![".DS_Store".to_string(), "*.map".to_string(), ".git/".to_string(), "*.swp".to_string()];
+        let include = vec!["vendor.js.map".to_string()];
+        let filter = BuildFilter::new(Path::new("/nonexistent/build"), &exclude, &include).unwrap();
+
+        assert!(filter.is_excluded(Path::new("static/.DS_Store"), false));
+        assert!(filter.is_excluded(Path::new("static/js/app.js.map"), false));
+        assert!(filter.is_excluded(Path::new("ru/.git"), true));
+        assert!(filter.is_excluded(Path::new(".index.html.swp"), false));
+        assert!(filter.is_excluded(Path::new(".mashinkaignore"), false));
+        assert!(!filter.is_excluded(Path::new("static/js/vendor.js.map"), false));
+        assert!(!filter.is_excluded(Path::new("index.html"), false));
+    }
+}
@@ -0,0 +1,291 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::command::Error;
+use crate::deploy::manifest::Manifest;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+
+/// id версии: время с миллисекундами, чтобы две выгрузки в одну секунду не перезаписали друг друга.
+const RELEASE_ID_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+const ARCHIVE_EXTENSION: &str = "tar.gz";
+const META_EXTENSION: &str = "meta";
+const MANIFEST_EXTENSION: &str = "manifest";
+const CURRENT_RELEASE_FILE_NAME: &str = "current";
+
+/// Выгруженная версия сайта: архив сборки и его метаданные.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Размер архива в байтах.
+    pub size: u64,
+    /// Директория, из которой собран архив.
+    pub build_path: String,
+    /// Контейнер, в который выгружена версия. У версий, сохраненных до появления поля, его нет.
+    pub container: Option<String>,
+}
+
+impl Release {
+    fn to_meta(&self) -> String {
+        let mut meta = format!(
+            "id: {}\ncreated: {}\nsize: {}\nbuild_path: {}\n",
+            self.id, self.created_at.format(ISO8601_DATE_TIME_FORMAT), self.size, self.build_path
+        );
+        if let Some(container) = &self.container {
+            meta.push_str(&format!("container: {container}\n"));
+        }
+        meta
+    }
+
+    /// Проверяет, что версия выгружалась в контейнер `container`, чтобы не выгрузить в него чужой сайт.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::ReleaseContainer` если версия выгружалась в другой контейнер.
+    pub fn check_container(&self, container: &str) -> Result<(), Error> {
+        match &self.container {
+            Some(release_container) if release_container != container => Err(Error::ReleaseContainer(
+                self.id.clone(), release_container.clone(), container.to_string()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn from_meta(content: &str) -> Result<Self, Error> {
+        let map: HashMap<&str, &str> = content.lines()
+            .filter_map(|line| line.split_once(": "))
+            .collect();
+
+        let field = |key: &str| map.get(key).map(ToString::to_string)
+            .ok_or(Error::IncorrectFormat(format!("Release meta has no `{key}` field")));
+
+        let created = NaiveDateTime::parse_from_str(&field("created")?, ISO8601_DATE_TIME_FORMAT)
+            .map_err(Error::DateTimeError)?;
+
+        Ok(Self {
+            id: field("id")?,
+            created_at: DateTime::<Utc>::from_utc(created, Utc),
+            size: field("size")?.parse().map_err(|_| Error::IncorrectFormat(String::from("Release size")))?,
+            build_path: field("build_path")?,
+            container: map.get("container").map(ToString::to_string),
+        })
+    }
+}
+
+/// Локальная история выгрузок. Каждая версия хранится как `{id}.tar.gz` и `{id}.meta`,
+/// а файл `current` содержит id версии, которая сейчас выгружена в хранилище.
+pub struct ReleaseHistory {
+    path: PathBuf,
+}
+
+impl ReleaseHistory {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf() }
+    }
+
+    pub fn archive_path(&self, release: &Release) -> PathBuf {
+        self.path.join(format!("{}.{ARCHIVE_EXTENSION}", release.id))
+    }
+
+    fn meta_path(&self, id: &str) -> PathBuf {
+        self.path.join(format!("{id}.{META_EXTENSION}"))
+    }
+
+    fn manifest_path(&self, id: &str) -> PathBuf {
+        self.path.join(format!("{id}.{MANIFEST_EXTENSION}"))
+    }
+
+    /// Возвращает манифест версии. Для версий без манифеста вернет `None`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если манифест поврежден.
+    pub fn manifest(&self, release: &Release) -> Result<Option<Manifest>, Error> {
+        let path = self.manifest_path(&release.id);
+        if !path.exists() { return Ok(None) }
+        Manifest::load(&path).map(Some)
+    }
+
+    /// Возвращает манифест выгруженной сейчас версии.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если манифест поврежден.
+    pub fn current_manifest(&self) -> Result<Option<Manifest>, Error> {
+        match self.current() {
+            Some(id) => self.manifest(&self.find(&id)?),
+            None => Ok(None),
+        }
+    }
+
+    /// Сохраняет копию архива `archive` и манифест `manifest` как новую версию, выгруженную в `container`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось создать директорию истории или скопировать архив, или если архив версии
+    /// с таким id уже есть.
+    pub fn add(&self, archive: &Path, build_path: &Path, manifest: &Manifest, container: &str) -> Result<Release, Error> {
+        fs::create_dir_all(&self.path).map_err(Error::CreateFile)?;
+
+        let created_at = Utc::now();
+        let mut id = created_at.format(RELEASE_ID_FORMAT).to_string();
+        for suffix in 1.. {
+            if !self.meta_path(&id).exists() { break }
+            id = format!("{}-{suffix}", created_at.format(RELEASE_ID_FORMAT));
+        }
+
+        let release = Release {
+            id,
+            created_at,
+            size: archive.metadata().map_err(Error::ReadFile)?.len(),
+            build_path: build_path.to_string_lossy().to_string(),
+            container: Some(container.to_string()),
+        };
+
+        let archive_path = self.archive_path(&release);
+        let mut target = fs::OpenOptions::new().write(true).create_new(true).open(&archive_path).map_err(|e| {
+            if e.kind() == io::ErrorKind::AlreadyExists {
+                Error::AlreadyExists(archive_path.display().to_string())
+            } else {
+                Error::WriteFile(e)
+            }
+        })?;
+        let mut source = fs::File::open(archive).map_err(Error::ReadFile)?;
+        io::copy(&mut source, &mut target).map_err(Error::WriteFile)?;
+        fs::write(self.meta_path(&release.id), release.to_meta()).map_err(Error::WriteFile)?;
+        manifest.save(&self.manifest_path(&release.id))?;
+
+        Ok(release)
+    }
+
+    /// Возвращает версии от старых к новым. Если истории еще нет, вернет пустой список.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если метаданные версии повреждены.
+    pub fn list(&self) -> Result<Vec<Release>, Error> {
+        let Ok(entries) = fs::read_dir(&self.path) else { return Ok(Vec::new()) };
+
+        let mut releases = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(Error::ReadDir)?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(META_EXTENSION) { continue }
+
+            let content = fs::read_to_string(&path).map_err(Error::ReadFile)?;
+            releases.push(Release::from_meta(&content)?);
+        }
+
+        releases.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(releases)
+    }
+
+    /// Возвращает версию по id.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если версии нет в истории.
+    pub fn find(&self, id: &str) -> Result<Release, Error> {
+        self.list()?.into_iter().find(|r| r.id == id)
+            .ok_or(Error::IncorrectFormat(format!("Release `{id}` not found in history")))
+    }
+
+    /// Возвращает id выгруженной сейчас версии.
+    pub fn current(&self) -> Option<String> {
+        fs::read_to_string(self.path.join(CURRENT_RELEASE_FILE_NAME)).ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Запоминает версию `release` как выгруженную.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось записать файл `current`.
+    pub fn set_current(&self, release: &Release) -> Result<(), Error> {
+        fs::write(self.path.join(CURRENT_RELEASE_FILE_NAME), &release.id).map_err(Error::WriteFile)
+    }
+
+    /// Возвращает версию, предшествующую выгруженной сейчас.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если в истории нет более ранней версии.
+    pub fn previous(&self) -> Result<Release, Error> {
+        let releases = self.list()?;
+        let current = self.current();
+        let position = releases.iter()
+            .position(|r| Some(&r.id) == current.as_ref())
+            .unwrap_or(releases.len());
+
+        position.checked_sub(1).and_then(|i| releases.get(i).cloned())
+            .ok_or(Error::IncorrectFormat(String::from("There is no previous release in history")))
+    }
+
+    /// Удаляет самые старые версии, оставляя `keep` последних и текущую.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось удалить файлы версии.
+    pub fn prune(&self, keep: usize) -> Result<(), Error> {
+        let releases = self.list()?;
+        let current = self.current();
+        let outdated = releases.len().saturating_sub(keep);
+
+        for release in releases.iter().take(outdated) {
+            if Some(&release.id) == current.as_ref() { continue }
+            fs::remove_file(self.archive_path(release)).map_err(Error::WriteFile)?;
+            fs::remove_file(self.meta_path(&release.id)).map_err(Error::WriteFile)?;
+
+            let manifest_path = self.manifest_path(&release.id);
+            if manifest_path.exists() {
+                fs::remove_file(manifest_path).map_err(Error::WriteFile)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::deploy::history::{Release, ReleaseHistory};
+    use crate::deploy::manifest::Manifest;
+
+    #[test]
+    fn test_release_meta_conversion() {
+        let release = Release {
+            id: "20230101-101010".to_string(),
+            created_at: Utc.with_ymd_and_hms(2023, 1, 1, 10, 10, 10).unwrap(),
+            size: 42,
+            build_path: "/blog/build".to_string(),
+            container: Some("blog".to_string()),
+        };
+
+        assert_eq!(release, Release::from_meta(&release.to_meta()).unwrap());
+        assert!(release.check_container("blog").is_ok());
+        assert!(release.check_container("team-blog").is_err());
+
+        let old = Release { container: None, ..release };
+        assert_eq!(old, Release::from_meta(&old.to_meta()).unwrap());
+        assert!(old.check_container("team-blog").is_ok());
+    }
+
+    #[test]
+    fn test_releases_in_same_second_are_kept() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let archive = dir.path().join("site.tar.gz");
+        std::fs::write(&archive, "archive").unwrap();
+        let history = ReleaseHistory::new(&dir.path().join("history"));
+
+        let first = history.add(&archive, dir.path(), &Manifest::default(), "blog").unwrap();
+        let second = history.add(&archive, dir.path(), &Manifest::default(), "blog").unwrap();
+
+        assert_ne!(first.id, second.id);
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|release| release.id).collect();
+        assert_eq!(vec![first.id, second.id], ids);
+    }
+}
@@ -0,0 +1,194 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use std::time::Duration;
+use std::{env, fs, process};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::command::Error;
+use crate::deploy::selectel::Selectel;
+use crate::grow::ISO8601_DATE_TIME_FORMAT;
+
+/// Объект в хранилище, который означает, что сейчас идет выгрузка.
+pub const LOCK_OBJECT_NAME: &str = ".mashinka.lock";
+
+/// Блокировка выгрузки: кто, с какой машины и когда начал выгрузку.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployLock {
+    /// Уникальный id блокировки, чтобы не снять чужую.
+    pub id: String,
+    pub owner: String,
+    pub host: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeployLock {
+    pub fn new() -> Self {
+        let created_at = Utc::now();
+        let owner = env::var("USER").or(env::var("USERNAME")).unwrap_or(String::from("unknown"));
+        let host = env::var("HOSTNAME").ok()
+            .or(fs::read_to_string("/etc/hostname").ok())
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .unwrap_or(String::from("unknown"));
+
+        Self {
+            id: format!("{}-{}", process::id(), created_at.timestamp_nanos()),
+            owner,
+            host,
+            created_at,
+        }
+    }
+
+    pub fn to_content(&self) -> String {
+        format!(
+            "id: {}\nowner: {}\nhost: {}\ncreated: {}\n",
+            self.id, self.owner, self.host, self.created_at.format(ISO8601_DATE_TIME_FORMAT)
+        )
+    }
+
+    /// # Errors
+    ///
+    /// Вернет `Error` если содержимое блокировки повреждено.
+    pub fn from_content(content: &str) -> Result<Self, Error> {
+        let map: HashMap<&str, &str> = content.lines()
+            .filter_map(|line| line.split_once(": "))
+            .collect();
+
+        let field = |key: &str| map.get(key).map(ToString::to_string)
+            .ok_or(Error::IncorrectFormat(format!("Deploy lock has no `{key}` field")));
+
+        let created = NaiveDateTime::parse_from_str(&field("created")?, ISO8601_DATE_TIME_FORMAT)
+            .map_err(Error::DateTimeError)?;
+
+        Ok(Self {
+            id: field("id")?,
+            owner: field("owner")?,
+            host: field("host")?,
+            created_at: DateTime::<Utc>::from_utc(created, Utc),
+        })
+    }
+
+    /// Блокировка старше `ttl` считается брошенной (например, выгрузка была убита).
+    pub fn is_stale(&self, ttl: Duration, now: DateTime<Utc>) -> bool {
+        let age = now.signed_duration_since(self.created_at).to_std().unwrap_or_default();
+        age > ttl
+    }
+}
+
+impl Default for DeployLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Возвращает текущую блокировку выгрузки, если она есть.
+///
+/// # Errors
+///
+/// Вернет `Error` если API вернуло ошибку или блокировка повреждена.
+pub fn current_lock(client: &Selectel) -> Result<Option<DeployLock>, Error> {
+    Ok(stored_lock(client)?.map(|(lock, _)| lock))
+}
+
+/// Текущая блокировка и ETag ее объекта.
+fn stored_lock(client: &Selectel) -> Result<Option<(DeployLock, String)>, Error> {
+    client.get_object_with_etag(LOCK_OBJECT_NAME)?
+        .map(|(content, etag)| DeployLock::from_content(&content).map(|lock| (lock, etag)))
+        .transpose()
+}
+
+/// Захватывает блокировку выгрузки. Объект блокировки создается условным запросом (`If-None-Match: *`),
+/// поэтому из двух одновременных запусков блокировку получает только один. Брошенная блокировка (старше
+/// `ttl`) удаляется запросом с ее ETag (`If-Match`): если ее успел заменить другой запуск, она не удаляется.
+///
+/// Блокировка не продлевается во время выгрузки, поэтому `ttl` должен быть больше самой долгой выгрузки.
+///
+/// # Errors
+///
+/// Вернет `Error::DeployLocked` если выгрузку уже кто-то выполняет.
+pub fn acquire(client: &Selectel, ttl: Duration) -> Result<DeployLock, Error> {
+    let lock = DeployLock::new();
+    let content = lock.to_content();
+
+    if client.create_content(LOCK_OBJECT_NAME, &content)? {
+        return Ok(lock);
+    }
+
+    match stored_lock(client)? {
+        // запрос мог быть повторен после обрыва соединения, хотя первая попытка создала объект
+        Some((stored, _)) if stored.id == lock.id => Ok(lock),
+        Some((stored, _)) if !stored.is_stale(ttl, Utc::now()) => Err(locked(&stored)),
+        Some((stored, etag)) => {
+            if client.delete_object_if_match(LOCK_OBJECT_NAME, &etag)? && client.create_content(LOCK_OBJECT_NAME, &content)? {
+                Ok(lock)
+            } else {
+                Err(current_lock(client)?.map_or(locked(&stored), |current| locked(&current)))
+            }
+        }
+        // блокировку сняли между запросами
+        None if client.create_content(LOCK_OBJECT_NAME, &content)? => Ok(lock),
+        None => Err(current_lock(client)?.map_or(
+            Error::DeployApi(String::from("Can't create deploy lock")),
+            |current| locked(&current),
+        )),
+    }
+}
+
+/// Снимает блокировку `lock`, если она все еще наша. Удаление условное (`If-Match`), чтобы не снять
+/// блокировку, которую успел поставить другой запуск.
+///
+/// # Errors
+///
+/// Вернет `Error` если API вернуло ошибку.
+pub fn release(client: &Selectel, lock: &DeployLock) -> Result<(), Error> {
+    if let Some((stored, etag)) = stored_lock(client)? {
+        if stored.id == lock.id {
+            client.delete_object_if_match(LOCK_OBJECT_NAME, &etag)?;
+        }
+    }
+    Ok(())
+}
+
+/// Выполняет `action` под блокировкой выгрузки. Блокировка снимается и при ошибке `action`.
+///
+/// # Errors
+///
+/// Вернет ошибку `action`, а если ее нет, то ошибку захвата или снятия блокировки.
+pub fn with_lock<T>(client: &Selectel, ttl: Duration, action: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    let lock = acquire(client, ttl)?;
+    let result = action();
+    let released = release(client, &lock);
+
+    let value = result?;
+    released?;
+    Ok(value)
+}
+
+fn locked(lock: &DeployLock) -> Error {
+    Error::DeployLocked(format!(
+        "{}@{} since {} UTC", lock.owner, lock.host, lock.created_at.format(ISO8601_DATE_TIME_FORMAT)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use chrono::{TimeZone, Utc};
+    use crate::deploy::lock::DeployLock;
+
+    #[test]
+    fn test_lock_content_conversion_and_staleness() {
+        let lock = DeployLock {
+            id: "1-2".to_string(),
+            owner: "viktor".to_string(),
+            host: "laptop".to_string(),
+            created_at: Utc.with_ymd_and_hms(2023, 1, 1, 10, 0, 0).unwrap(),
+        };
+
+        assert_eq!(lock, DeployLock::from_content(&lock.to_content()).unwrap());
+
+        let now = Utc.with_ymd_and_hms(2023, 1, 1, 10, 20, 0).unwrap();
+        assert!(!lock.is_stale(Duration::from_secs(30 * 60), now));
+        assert!(lock.is_stale(Duration::from_secs(10 * 60), now));
+    }
+}
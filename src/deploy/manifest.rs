@@ -0,0 +1,177 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use flate2::Crc;
+use crate::command::Error;
+use crate::deploy::archive::object_key;
+
+/// Файл сборки: ключ объекта в хранилище, размер и контрольная сумма (crc32) содержимого.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub key: String,
+    pub size: u64,
+    pub checksum: u32,
+}
+
+/// Список файлов сборки. Сохраняется вместе с выгруженной версией, чтобы при следующей
+/// выгрузке показать, какие файлы добавлены, изменены или удалены.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+/// Разница между двумя манифестами.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Manifest {
+    /// Строит манифест для файлов `files` (пути относительно `build_path`).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если какой-либо файл не удалось прочитать.
+    pub fn build(build_path: &Path, files: &[PathBuf]) -> Result<Self, Error> {
+        let mut entries = BTreeMap::new();
+
+        for relative_path in files {
+            let mut file = File::open(build_path.join(relative_path)).map_err(Error::ReadFile)?;
+            let mut crc = Crc::new();
+            let mut buffer = [0u8; 64 * 1024];
+            let mut size = 0u64;
+
+            loop {
+                let read = file.read(&mut buffer).map_err(Error::ReadFile)?;
+                if read == 0 { break }
+                crc.update(&buffer[..read]);
+                size += read as u64;
+            }
+
+            let key = object_key(relative_path);
+            entries.insert(key.clone(), ManifestEntry { key, size, checksum: crc.sum() });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Загружает манифест, сохраненный `Manifest::save`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не удалось прочитать или он имеет неверный формат.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path).map_err(Error::ReadFile)?;
+        let mut entries = BTreeMap::new();
+
+        for line in content.lines().filter(|l| !l.is_empty()) {
+            let incorrect = || Error::IncorrectFormat(format!("Manifest line should be `key<TAB>size<TAB>crc32`, got `{line}`"));
+            let mut parts = line.rsplitn(3, '\t');
+            let checksum = parts.next().and_then(|v| u32::from_str_radix(v, 16).ok()).ok_or_else(incorrect)?;
+            let size = parts.next().and_then(|v| v.parse().ok()).ok_or_else(incorrect)?;
+            let key = parts.next().ok_or_else(incorrect)?.to_string();
+
+            entries.insert(key.clone(), ManifestEntry { key, size, checksum });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Сохраняет манифест в файл `path`, по одному файлу сборки на строку.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не удалось записать.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let content: String = self.entries.values()
+            .map(|e| format!("{}\t{}\t{:08x}\n", e.key, e.size, e.checksum))
+            .collect();
+
+        fs::write(path, content).map_err(Error::WriteFile)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.entries.values().map(|e| e.size).sum()
+    }
+
+    /// Возвращает `count` самых больших файлов, от большего к меньшему.
+    pub fn largest(&self, count: usize) -> Vec<&ManifestEntry> {
+        let mut entries: Vec<&ManifestEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| b.size.cmp(&a.size).then(a.key.cmp(&b.key)));
+        entries.truncate(count);
+        entries
+    }
+
+    /// Сравнивает манифест с предыдущим `previous`.
+    pub fn diff(&self, previous: &Manifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+
+        for (key, entry) in &self.entries {
+            match previous.entries.get(key) {
+                None => diff.added.push(key.clone()),
+                Some(old) if old != entry => diff.changed.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+
+        diff.removed = previous.entries.keys()
+            .filter(|key| !self.entries.contains_key(*key))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::deploy::manifest::{Manifest, ManifestDiff, ManifestEntry};
+
+    fn manifest(entries: &[(&str, u64, u32)]) -> Manifest {
+        let entries: BTreeMap<String, ManifestEntry> = entries.iter()
+            .map(|(key, size, checksum)| (key.to_string(), ManifestEntry { key: key.to_string(), size: *size, checksum: *checksum }))
+            .collect();
+        Manifest { entries }
+    }
+
+    #[test]
+    fn test_manifest_diff() {
+        let previous = manifest(&[("index.html", 10, 1), ("old.html", 5, 2), ("style.css", 3, 3)]);
+        let current = manifest(&[("index.html", 10, 1), ("new.html", 7, 4), ("style.css", 3, 5)]);
+
+        let expected = ManifestDiff {
+            added: vec!["new.html".to_string()],
+            changed: vec!["style.css".to_string()],
+            removed: vec!["old.html".to_string()],
+        };
+
+        assert_eq!(expected, current.diff(&previous));
+    }
+
+    #[test]
+    fn test_manifest_largest_files() {
+        let current = manifest(&[("a", 1, 0), ("b", 30, 0), ("c", 20, 0)]);
+        let largest: Vec<&str> = current.largest(2).iter().map(|e| e.key.as_str()).collect();
+
+        assert_eq!(vec!["b", "c"], largest);
+    }
+}
@@ -0,0 +1,184 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use globset::{Glob, GlobMatcher};
+use crate::command::Error;
+
+pub const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Типы содержимого по расширению файла. Переопределяются через `DEPLOY_MIME_TYPES`.
+const MIME_TYPES: [(&str, &str); 22] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "application/javascript; charset=utf-8"),
+    ("mjs", "application/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("xml", "application/xml"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("ico", "image/x-icon"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("pdf", "application/pdf"),
+    ("mp4", "video/mp4"),
+    ("webmanifest", "application/manifest+json"),
+];
+
+/// Кодировки предварительно сжатых копий файлов по расширению.
+const CONTENT_ENCODINGS: [(&str, &str); 2] = [("gz", "gzip"), ("br", "br")];
+
+/// Правила метаданных объектов при выгрузке по файлам: `Content-Type` по расширению,
+/// `Cache-Control` по glob и `Content-Encoding` для сжатых копий `.gz`/`.br`.
+#[derive(Default)]
+pub struct MetadataRules {
+    mime_types: HashMap<String, String>,
+    cache_control: Vec<(GlobMatcher, String)>,
+}
+
+impl MetadataRules {
+    /// `mime_types` - пары (расширение, тип), `cache_control` - пары (glob, значение заголовка).
+    /// Правила `Cache-Control` проверяются по порядку, применяется первое совпавшее.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если glob имеет неверный формат.
+    pub fn new(mime_types: &[(String, String)], cache_control: &[(String, String)]) -> Result<Self, Error> {
+        let mime_types = mime_types.iter()
+            .map(|(ext, mime)| (ext.trim_start_matches('.').to_lowercase(), mime.clone()))
+            .collect();
+
+        let mut rules = Vec::new();
+        for (pattern, value) in cache_control {
+            let matcher = Glob::new(pattern)
+                .map_err(|e| Error::IncorrectFormat(format!("Cache-Control pattern: {e}")))?
+                .compile_matcher();
+            rules.push((matcher, value.clone()));
+        }
+
+        Ok(Self { mime_types, cache_control: rules })
+    }
+
+    /// Заданы ли пользовательские правила.
+    pub fn is_empty(&self) -> bool {
+        self.mime_types.is_empty() && self.cache_control.is_empty()
+    }
+
+    /// Нужна ли выгрузка по файлам, чтобы объекты `keys` получили свои заголовки: распаковка архива в
+    /// хранилище их не задает. Нужна, если заданы пользовательские правила или среди файлов есть сжатые копии
+    /// (`Content-Encoding`). Обычные файлы выгружаются архивом, их `Content-Type` определяет хранилище.
+    pub fn requires_per_file(&self, keys: &HashSet<String>) -> bool {
+        !self.is_empty() || keys.iter().any(|key| original_key(key).is_some_and(|original| keys.contains(original)))
+    }
+
+    /// Возвращает заголовки для объекта `key`. `has_original` - есть ли среди выгружаемых файлов
+    /// несжатый оригинал, тогда `.gz`/`.br` файл считается его сжатой копией.
+    pub fn headers_for(&self, key: &str, has_original: bool) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        // для сжатой копии тип и правила кэширования берутся по оригиналу
+        let mut original = key;
+
+        if let (Some(stripped), true) = (original_key(key), has_original) {
+            let extension = key.rsplit('.').next().unwrap_or_default();
+            if let Some((_, encoding)) = CONTENT_ENCODINGS.iter().find(|(ext, _)| *ext == extension) {
+                headers.push(("Content-Encoding", (*encoding).to_string()));
+            }
+            original = stripped;
+        }
+
+        let extension = Path::new(original).extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        headers.push(("Content-Type", self.mime_type(&extension)));
+
+        if let Some((_, value)) = self.cache_control.iter().find(|(matcher, _)| matcher.is_match(original)) {
+            headers.push(("Cache-Control", value.clone()));
+        }
+
+        headers
+    }
+
+    /// MIME тип по расширению файла без точки: сначала из пользовательских правил, затем из встроенной таблицы.
+    pub fn mime_type(&self, extension: &str) -> String {
+        if let Some(mime) = self.mime_types.get(extension) {
+            return mime.clone();
+        }
+
+        MIME_TYPES.iter()
+            .find(|(ext, _)| *ext == extension)
+            .map_or(DEFAULT_MIME_TYPE, |(_, mime)| mime)
+            .to_string()
+    }
+}
+
+/// Возвращает ключ несжатого оригинала для `.gz`/`.br` копии.
+pub fn original_key(key: &str) -> Option<&str> {
+    CONTENT_ENCODINGS.iter()
+        .find_map(|(ext, _)| key.strip_suffix(&format!(".{ext}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::deploy::metadata::{MetadataRules, original_key};
+
+    fn rules() -> MetadataRules {
+        MetadataRules::new(
+            &[("json".to_string(), "application/json; charset=utf-8".to_string())],
+            &[("*.css".to_string(), "public, max-age=31536000".to_string()), ("*".to_string(), "no-cache".to_string())],
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_headers_for_plain_files() {
+        let rules = rules();
+
+        assert_eq!(
+            vec![("Content-Type", "text/css; charset=utf-8".to_string()), ("Cache-Control", "public, max-age=31536000".to_string())],
+            rules.headers_for("static/css/main.css", false)
+        );
+        assert_eq!(
+            vec![("Content-Type", "application/json; charset=utf-8".to_string()), ("Cache-Control", "no-cache".to_string())],
+            rules.headers_for("index/data.json", false)
+        );
+    }
+
+    #[test]
+    fn test_headers_for_precompressed_files() {
+        let rules = rules();
+
+        assert_eq!(
+            vec![
+                ("Content-Encoding", "br".to_string()),
+                ("Content-Type", "text/css; charset=utf-8".to_string()),
+                ("Cache-Control", "public, max-age=31536000".to_string()),
+            ],
+            rules.headers_for("static/css/main.css.br", true)
+        );
+        assert_eq!(Some("static/css/main.css"), original_key("static/css/main.css.gz"));
+        assert_eq!(None, original_key("index.html"));
+    }
+
+    #[test]
+    fn test_requires_per_file_without_custom_rules() {
+        let keys = |keys: &[&str]| keys.iter().map(ToString::to_string).collect::<HashSet<String>>();
+        let rules = MetadataRules::default();
+
+        // обычная сборка выгружается архивом
+        assert!(!rules.requires_per_file(&keys(&["index.html", "static/css/main.css", "static/js/app.js", "index/data.json"])));
+        assert!(!rules.requires_per_file(&keys(&["archive.unknown", "LICENSE", "backup.gz"])));
+        assert!(rules.requires_per_file(&keys(&["static/css/main.css", "static/css/main.css.gz"])));
+        assert!(rules.requires_per_file(&keys(&["archive.unknown", "archive.unknown.br"])));
+
+        let custom = MetadataRules::new(&[], &[("*.css".to_string(), "no-cache".to_string())]).unwrap();
+        assert!(custom.requires_per_file(&keys(&["index.html"])));
+    }
+}
@@ -0,0 +1,141 @@
+#![allow(clippy::must_use_candidate)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+use std::cell::Cell;
+use std::io::{IsTerminal, Read, Write};
+use std::time::{Duration, Instant};
+use crate::deploy::report::format_size;
+
+/// Как часто перерисовывать строку прогресса в терминале.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+/// Как часто писать строку лога, если вывод не в терминал.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+const BAR_WIDTH: usize = 30;
+
+/// Прогресс долгой операции в байтах: отправлено, скорость и оставшееся время.
+/// Выводится в stderr, чтобы не смешиваться с результатом команды. Если stderr терминал, рисуется
+/// обновляемая строка с полосой, иначе раз в несколько секунд пишется строка лога.
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: Cell<u64>,
+    started_at: Cell<Instant>,
+    printed_at: Cell<Option<Instant>>,
+    is_terminal: bool,
+}
+
+impl Progress {
+    pub fn new(label: &str, total: u64) -> Self {
+        Self {
+            label: label.to_string(),
+            total,
+            done: Cell::new(0),
+            started_at: Cell::new(Instant::now()),
+            printed_at: Cell::new(None),
+            is_terminal: std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// Начинает отсчет заново, например перед повтором запроса.
+    pub fn reset(&self) {
+        self.done.set(0);
+        self.started_at.set(Instant::now());
+    }
+
+    pub fn advance(&self, bytes: u64) {
+        self.done.set(self.done.get().saturating_add(bytes));
+
+        let interval = if self.is_terminal { REDRAW_INTERVAL } else { LOG_INTERVAL };
+        let last = self.printed_at.get().unwrap_or(self.started_at.get());
+        if last.elapsed() >= interval {
+            self.print();
+        }
+    }
+
+    /// Выводит итог. Если операция прошла быстрее, чем появилась первая строка прогресса, ничего не выводит.
+    pub fn finish(&self) {
+        if self.printed_at.get().is_none() {
+            return;
+        }
+
+        self.print();
+        if self.is_terminal {
+            eprintln!();
+        }
+    }
+
+    fn print(&self) {
+        self.printed_at.set(Some(Instant::now()));
+        let line = self.to_line(self.started_at.get().elapsed());
+
+        if self.is_terminal {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "\r\x1b[2K{line}");
+            let _ = stderr.flush();
+        } else {
+            eprintln!("{line}");
+        }
+    }
+
+    /// Строка вида `upload [=====>    ] 12.0 MB / 50.0 MB 1.5 MB/s ETA 0:25`.
+    pub fn to_line(&self, elapsed: Duration) -> String {
+        let done = self.done.get().min(self.total);
+        let rate = if elapsed.as_secs_f64() > 0.0 { done as f64 / elapsed.as_secs_f64() } else { 0.0 };
+        let eta = if rate > 0.0 {
+            let secs = ((self.total - done) as f64 / rate).ceil() as u64;
+            format!("{}:{:02}", secs / 60, secs % 60)
+        } else {
+            String::from("-:--")
+        };
+
+        let sizes = format!("{} / {}", format_size(done), format_size(self.total));
+        let speed = format!("{}/s", format_size(rate as u64));
+
+        if !self.is_terminal {
+            return format!("{}: {sizes} {speed} ETA {eta}", self.label);
+        }
+
+        let filled = if self.total == 0 { BAR_WIDTH } else { (done as f64 / self.total as f64 * BAR_WIDTH as f64) as usize };
+        let bar = format!("{}{}", "=".repeat(filled), " ".repeat(BAR_WIDTH - filled));
+        format!("{} [{bar}] {sizes} {speed} ETA {eta}", self.label)
+    }
+}
+
+/// Обертка над `Read`, которая сообщает `Progress` о прочитанных байтах.
+pub struct ProgressReader<'a, R: Read> {
+    inner: R,
+    progress: &'a Progress,
+}
+
+impl<'a, R: Read> ProgressReader<'a, R> {
+    pub fn new(inner: R, progress: &'a Progress) -> Self {
+        Self { inner, progress }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.advance(read as u64);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::time::Duration;
+    use crate::deploy::progress::{Progress, ProgressReader};
+
+    #[test]
+    fn test_progress_line() {
+        let progress = Progress { is_terminal: false, ..Progress::new("upload", 4 * 1024 * 1024) };
+        let mut reader = ProgressReader::new(&[0u8; 1024 * 1024][..], &progress);
+        std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+        assert_eq!(0, reader.read(&mut [0u8; 16]).unwrap());
+
+        assert_eq!("upload: 1.0 MB / 4.0 MB 512.0 KB/s ETA 0:06", progress.to_line(Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::command::Error;
+use crate::deploy::lock::LOCK_OBJECT_NAME;
+use crate::deploy::manifest::Manifest;
+
+/// Возвращает объекты хранилища, которых нет среди локальных файлов сборки. Служебные объекты
+/// (блокировка выгрузки) не удаляются.
+pub fn find_orphans(remote: &[String], manifest: &Manifest) -> Vec<String> {
+    remote.iter()
+        .filter(|key| !manifest.contains(key) && key.as_str() != LOCK_OBJECT_NAME)
+        .cloned()
+        .collect()
+}
+
+/// Проверяет, что удаляется не больше `max_percent` процентов объектов хранилища. Защищает от
+/// удаления всего сайта, например при выгрузке из пустой или не той директории.
+///
+/// # Errors
+///
+/// Вернет `Error::PruneLimit` если порог превышен и не задан `force`.
+pub fn check_threshold(orphans: usize, total: usize, max_percent: u64, force: bool) -> Result<(), Error> {
+    if force || orphans == 0 {
+        return Ok(());
+    }
+
+    if (orphans as u128) * 100 > (total as u128) * u128::from(max_percent) {
+        return Err(Error::PruneLimit(orphans, total, max_percent));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deploy::prune::check_threshold;
+
+    #[test]
+    fn test_check_threshold() {
+        assert!(check_threshold(1, 10, 10, false).is_ok());
+        assert!(check_threshold(2, 10, 10, false).is_err());
+        assert!(check_threshold(10, 10, 10, true).is_ok());
+        assert!(check_threshold(0, 0, 0, false).is_ok());
+    }
+}
@@ -0,0 +1,93 @@
+#![allow(clippy::must_use_candidate)]
+#![allow(clippy::cast_precision_loss)]
+
+use crate::command::escape_json;
+use crate::deploy::manifest::{Manifest, ManifestDiff};
+
+/// Сколько самых больших файлов показывать в отчете.
+pub const LARGEST_FILES_COUNT: usize = 10;
+
+/// Отчет о том, что будет выгружено: количество и размер файлов, размер архива, самые большие
+/// файлы и, если есть предыдущий манифест, добавленные, измененные и удаленные файлы.
+pub struct DeployReport<'a> {
+    pub manifest: &'a Manifest,
+    pub compressed_size: u64,
+    pub diff: Option<ManifestDiff>,
+}
+
+impl DeployReport<'_> {
+    /// Строки отчета в виде (ключ, значение) для вывода через `Details`.
+    pub fn to_lines(&self) -> Vec<(String, String)> {
+        let mut lines = vec![
+            (String::from("files"), self.manifest.len().to_string()),
+            (String::from("total_size"), format_size(self.manifest.total_size())),
+            (String::from("compressed_size"), format_size(self.compressed_size)),
+        ];
+
+        for entry in self.manifest.largest(LARGEST_FILES_COUNT) {
+            lines.push((String::from("largest"), format!("{} {}", entry.key, format_size(entry.size))));
+        }
+
+        if let Some(diff) = &self.diff {
+            for (name, keys) in [("added", &diff.added), ("changed", &diff.changed), ("removed", &diff.removed)] {
+                lines.push((name.to_string(), keys.len().to_string()));
+                lines.extend(keys.iter().map(|key| (String::new(), format!("  {name} {key}"))));
+            }
+        }
+
+        lines
+    }
+
+    pub fn to_json(&self) -> String {
+        let largest: Vec<String> = self.manifest.largest(LARGEST_FILES_COUNT).iter()
+            .map(|e| format!(r#"{{"key": "{}", "size": {}}}"#, escape_json(&e.key), e.size))
+            .collect();
+
+        let diff = self.diff.as_ref().map_or(String::from("null"), |diff| {
+            let list = |keys: &Vec<String>| keys.iter()
+                .map(|k| format!(r#""{}""#, escape_json(k)))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            format!(
+                r#"{{"added": [{}], "changed": [{}], "removed": [{}]}}"#,
+                list(&diff.added), list(&diff.changed), list(&diff.removed)
+            )
+        });
+
+        format!(
+            r#"{{"files": {}, "total_size": {}, "compressed_size": {}, "largest": [{}], "diff": {}}}"#,
+            self.manifest.len(), self.manifest.total_size(), self.compressed_size, largest.join(", "), diff
+        )
+    }
+}
+
+/// Форматирует размер в байтах для человека: 512 B, 1.5 KB, 2.0 MB.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deploy::report::format_size;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!("512 B", format_size(512));
+        assert_eq!("1.5 KB", format_size(1536));
+        assert_eq!("2.0 MB", format_size(2 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,107 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::thread;
+use std::time::Duration;
+use crate::command::Error;
+use crate::deploy::MAX_RETRY_DELAY_SECS;
+
+/// Политика повторов запросов к API облачного хранилища. Повторяются только временные ошибки:
+/// ошибки транспорта (таймауты, обрывы соединения), 429 и 5xx ответы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Общее количество попыток, включая первую.
+    attempts: u32,
+    /// Задержка перед первым повтором. Каждая следующая задержка удваивается.
+    delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(500))
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(attempts: u32, delay: Duration) -> Self {
+        Self { attempts: attempts.max(1), delay }
+    }
+
+    /// Задержка перед повтором номер `retry` (начиная с 1) с экспоненциальным ростом.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(Duration::from_secs(MAX_RETRY_DELAY_SECS))
+    }
+
+    /// Выполняет `request`, повторяя его при временных ошибках.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::DeployApi` если запрос завершился постоянной ошибкой или закончились попытки.
+    pub fn run<T>(&self, mut request: impl FnMut() -> Result<T, ureq::Error>) -> Result<T, Error> {
+        let mut attempt = 1;
+        loop {
+            match request() {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.attempts && is_transient(&e) => {
+                    thread::sleep(self.backoff(attempt));
+                    attempt += 1;
+                }
+                Err(e) => return Err(Error::DeployApi(e.to_string())),
+            }
+        }
+    }
+}
+
+fn is_transient(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)]
+mod tests {
+    use std::time::Duration;
+    use crate::deploy::retry::RetryPolicy;
+
+    fn status(code: u16) -> ureq::Error {
+        ureq::Error::Status(code, ureq::Response::new(code, "status", "").unwrap())
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially_and_is_capped() {
+        let policy = RetryPolicy::new(10, Duration::from_millis(100));
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(400), policy.backoff(3));
+        assert_eq!(Duration::from_secs(30), policy.backoff(20));
+    }
+
+    #[test]
+    fn test_retry_transient_errors_until_success() {
+        let policy = RetryPolicy::new(3, Duration::ZERO);
+        let mut calls = 0;
+
+        let result = policy.run(|| {
+            calls += 1;
+            if calls < 3 { Err(status(503)) } else { Ok(calls) }
+        });
+
+        assert_eq!(Ok(3), result.map_err(|e| e.to_string()));
+    }
+
+    #[test]
+    fn fail_without_retry_on_client_error() {
+        let policy = RetryPolicy::new(3, Duration::ZERO);
+        let mut calls = 0;
+
+        let result: Result<(), _> = policy.run(|| {
+            calls += 1;
+            Err(status(403))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(1, calls);
+    }
+}
@@ -0,0 +1,277 @@
+#![allow(clippy::result_large_err)]
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use crate::command::Error;
+use crate::config::{cache_dir, DeployConfig};
+use crate::deploy::progress::{Progress, ProgressReader};
+use crate::deploy::retry::RetryPolicy;
+use crate::deploy::token::CachedToken;
+use crate::deploy::{LIST_OBJECTS_LIMIT, SELECTEL_API_URL, SELECTEL_AUTH_URL, TOKEN_FILE_NAME};
+
+/// Клиент API облачного хранилища Selectel (Swift). Все запросы повторяются согласно `RetryPolicy`.
+/// Токен кешируется в каталоге кеша и переполучается, если API ответило 401.
+pub struct Selectel {
+    config: DeployConfig,
+    retry: RetryPolicy,
+    token: RefCell<String>,
+    token_path: Option<PathBuf>,
+}
+
+impl Selectel {
+    /// Берет токен из кеша или авторизуется в API и возвращает готовый к работе клиент.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::DeployApi` если не удалось получить токен.
+    pub fn connect(config: DeployConfig, retry: RetryPolicy) -> Result<Self, Error> {
+        let token_path = cache_dir().ok().map(|dir| dir.join(TOKEN_FILE_NAME));
+        let cached = token_path.as_deref().and_then(|path| CachedToken::load(path, &config.username));
+
+        let client = Self { config, retry, token: RefCell::new(String::new()), token_path };
+        match cached {
+            Some(cached) => client.token.replace(cached.token),
+            None => client.authenticate()?,
+        };
+
+        Ok(client)
+    }
+
+    /// Контейнер, в который выгружает клиент.
+    pub fn container(&self) -> String {
+        self.config.container_id.clone()
+    }
+
+    /// Куда выгружает клиент, см. `DeployConfig::target`.
+    pub fn target(&self) -> String {
+        self.config.target()
+    }
+
+    /// Получает новый токен и сохраняет его в кеш. Возвращает предыдущий токен.
+    fn authenticate(&self) -> Result<String, Error> {
+        let (token, expire_secs) = fetch_token(&self.config, &self.retry)?;
+
+        if let (Some(path), Some(expire_secs)) = (&self.token_path, expire_secs) {
+            // Кеш токена не обязателен: если его не удалось записать, просто авторизуемся в следующий раз
+            let _ = CachedToken::new(&self.config.username, &token, expire_secs, Utc::now()).save(path);
+        }
+
+        Ok(self.token.replace(token))
+    }
+
+    /// Выполняет запрос с текущим токеном. Если токен отозван или истек раньше срока и API ответило 401,
+    /// получает новый токен и повторяет запрос.
+    fn request<T>(&self, mut request: impl FnMut(&str) -> Result<T, ureq::Error>) -> Result<T, Error> {
+        let mut unauthorized = false;
+        let result = self.retry.run(|| {
+            let response = request(self.token.borrow().as_str());
+            unauthorized = matches!(response, Err(ureq::Error::Status(401, _)));
+            response
+        });
+
+        if !unauthorized {
+            return result;
+        }
+
+        self.authenticate()?;
+        self.retry.run(|| request(self.token.borrow().as_str()))
+    }
+
+    fn container_url(&self) -> String {
+        let account_id = self.config.account_id.as_str();
+        let container_id = self.config.container_id.as_str();
+        format!("{SELECTEL_API_URL}/SEL_{account_id}/{container_id}/")
+    }
+
+    /// Выгружает архив в хранилище и распаковывает его в корень. Архив читается с диска потоком,
+    /// прогресс выгрузки выводится в stderr.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось прочитать архив или API вернуло ошибку.
+    pub fn upload_and_extract(&self, archive: &Path) -> Result<(), Error> {
+        let endpoint = self.container_url();
+        let size = archive.metadata().map_err(Error::ReadFile)?.len();
+        let progress = Progress::new("upload", size);
+
+        self.request(|token| {
+            progress.reset();
+            let file = File::open(archive)?;
+            ureq::put(endpoint.as_str())
+                .set("X-Auth-Token", token)
+                .set("Content-Length", &size.to_string())
+                .query("extract-archive", "tar.gz")
+                .send(ProgressReader::new(file, &progress))
+        })?;
+
+        progress.finish();
+        Ok(())
+    }
+
+    /// Выгружает файл `file` в объект `key` контейнера с заголовками `headers`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось прочитать файл или API вернуло ошибку.
+    pub fn put_object(&self, key: &str, file: &Path, headers: &[(&str, String)]) -> Result<(), Error> {
+        let endpoint = format!("{}{}", self.container_url(), encode_key(key));
+        let size = file.metadata().map_err(Error::ReadFile)?.len();
+
+        self.request(|token| {
+            let content = File::open(file)?;
+            let request = ureq::put(endpoint.as_str())
+                .set("X-Auth-Token", token)
+                .set("Content-Length", &size.to_string());
+
+            headers.iter()
+                .fold(request, |request, (name, value)| request.set(name, value))
+                .send(content)
+        })?;
+
+        Ok(())
+    }
+
+    /// Возвращает ключи всех объектов контейнера.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло ошибку.
+    pub fn list_objects(&self) -> Result<Vec<String>, Error> {
+        let endpoint = self.container_url();
+        let mut keys: Vec<String> = Vec::new();
+
+        loop {
+            let marker = keys.last().cloned().unwrap_or_default();
+            let response = self.request(|token| {
+                ureq::get(endpoint.as_str())
+                    .set("X-Auth-Token", token)
+                    .query("limit", &LIST_OBJECTS_LIMIT.to_string())
+                    .query("marker", &marker)
+                    .call()
+            })?;
+
+            let body = response.into_string().map_err(|e| Error::DeployApi(e.to_string()))?;
+            let page: Vec<String> = body.lines().filter(|l| !l.is_empty()).map(ToString::to_string).collect();
+            let is_last = page.len() < LIST_OBJECTS_LIMIT;
+            keys.extend(page);
+
+            if is_last {
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// Возвращает содержимое объекта `key` или `None`, если объекта нет.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло ошибку.
+    pub fn get_object(&self, key: &str) -> Result<Option<String>, Error> {
+        Ok(self.get_object_with_etag(key)?.map(|(content, _)| content))
+    }
+
+    /// Возвращает содержимое объекта `key` и его ETag или `None`, если объекта нет.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло ошибку.
+    pub fn get_object_with_etag(&self, key: &str) -> Result<Option<(String, String)>, Error> {
+        let endpoint = format!("{}{}", self.container_url(), encode_key(key));
+
+        let response = self.request(|token| {
+            match ureq::get(endpoint.as_str()).set("X-Auth-Token", token).call() {
+                Ok(response) => Ok(Some(response)),
+                Err(ureq::Error::Status(404, _)) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })?;
+
+        response.map(|r| {
+            let etag = r.header("ETag").unwrap_or_default().trim_matches('"').to_string();
+            r.into_string().map(|content| (content, etag)).map_err(|e| Error::DeployApi(e.to_string()))
+        }).transpose()
+    }
+
+    /// Создает объект `key` со строкой `content`, только если такого объекта еще нет (`If-None-Match: *`).
+    /// Возвращает `false`, если объект уже есть: API ответило 412.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло другую ошибку.
+    pub fn create_content(&self, key: &str, content: &str) -> Result<bool, Error> {
+        let endpoint = format!("{}{}", self.container_url(), encode_key(key));
+
+        self.request(|token| {
+            match ureq::put(endpoint.as_str()).set("X-Auth-Token", token).set("If-None-Match", "*").send_string(content) {
+                Ok(_) => Ok(true),
+                Err(ureq::Error::Status(412, _)) => Ok(false),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    /// Удаляет объект `key` из контейнера. Если объекта уже нет (404), например его удалили после получения
+    /// списка или повтор пришел после удачного удаления, то это не ошибка.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло другую ошибку.
+    pub fn delete_object(&self, key: &str) -> Result<(), Error> {
+        let endpoint = format!("{}{}", self.container_url(), encode_key(key));
+
+        self.request(|token| {
+            match ureq::delete(endpoint.as_str()).set("X-Auth-Token", token).call() {
+                Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    /// Удаляет объект `key`, только если его ETag все еще `etag` (`If-Match`). Возвращает `false`, если объект
+    /// успели изменить (412) или удалить (404).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если API вернуло другую ошибку.
+    pub fn delete_object_if_match(&self, key: &str, etag: &str) -> Result<bool, Error> {
+        let endpoint = format!("{}{}", self.container_url(), encode_key(key));
+
+        self.request(|token| {
+            match ureq::delete(endpoint.as_str()).set("X-Auth-Token", token).set("If-Match", etag).call() {
+                Ok(_) => Ok(true),
+                Err(ureq::Error::Status(404 | 412, _)) => Ok(false),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
+
+/// Получает токен для работы с API Selectel и время его жизни в секундах, если API его сообщило.
+fn fetch_token(deploy_config: &DeployConfig, retry: &RetryPolicy) -> Result<(String, Option<i64>), Error> {
+    let response = retry.run(|| {
+        ureq::get(SELECTEL_AUTH_URL)
+            .set("X-Auth-User", deploy_config.username.as_str())
+            .set("X-Auth-Key", deploy_config.password.as_str())
+            .call()
+    })?;
+
+    let token = response.header("X-Storage-Token")
+        .ok_or(Error::DeployApi("Key X-Storage-Token does not exists".to_string()))?;
+    let expire_secs = response.header("X-Expire-Auth-Token").and_then(|secs| secs.trim().parse().ok());
+
+    Ok((String::from(token), expire_secs))
+}
+
+/// Кодирует ключ объекта для использования в URL. Разделитель `/` сохраняется.
+fn encode_key(key: &str) -> String {
+    let mut encoded = String::new();
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
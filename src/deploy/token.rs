@@ -0,0 +1,98 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use chrono::{DateTime, TimeZone, Utc};
+use crate::command::Error;
+
+/// Сколько секунд до истечения токен уже считается истекшим, чтобы он не протух посреди выгрузки.
+const EXPIRE_MARGIN_SECS: i64 = 60;
+
+/// Токен API хранилища, сохраненный между запусками.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedToken {
+    /// Пользователь, для которого получен токен.
+    pub username: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CachedToken {
+    /// Создает токен, который истекает через `expire_secs` секунд (значение `X-Expire-Auth-Token`).
+    pub fn new(username: &str, token: &str, expire_secs: i64, now: DateTime<Utc>) -> Self {
+        Self {
+            username: username.to_string(),
+            token: token.to_string(),
+            expires_at: now + chrono::Duration::seconds(expire_secs),
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now + chrono::Duration::seconds(EXPIRE_MARGIN_SECS) >= self.expires_at
+    }
+
+    pub fn to_content(&self) -> String {
+        format!("{}\n{}\n{}\n", self.username, self.token, self.expires_at.timestamp())
+    }
+
+    pub fn from_content(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let username = lines.next()?.to_string();
+        let token = lines.next().filter(|t| !t.is_empty())?.to_string();
+        let expires_at = Utc.timestamp_opt(lines.next()?.parse().ok()?, 0).single()?;
+
+        Some(Self { username, token, expires_at })
+    }
+
+    /// Читает токен пользователя `username` из файла `path`. Вернет `None` если файла нет,
+    /// он поврежден, токен получен для другого пользователя или уже истек.
+    pub fn load(path: &Path, username: &str) -> Option<Self> {
+        fs::read_to_string(path).ok()
+            .and_then(|content| Self::from_content(&content))
+            .filter(|cached| cached.username == username && !cached.is_expired(Utc::now()))
+    }
+
+    /// Сохраняет токен в файл `path`, доступный только текущему пользователю.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::WriteFile` если не удалось записать файл.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(Error::WriteFile)?;
+        }
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            if path.exists() {
+                fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(Error::WriteFile)?;
+            }
+        }
+
+        let mut file = options.open(path).map_err(Error::WriteFile)?;
+        file.write_all(self.to_content().as_bytes()).map_err(Error::WriteFile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::deploy::token::CachedToken;
+
+    #[test]
+    fn test_cached_token_expiration() {
+        let now = Utc.with_ymd_and_hms(2023, 1, 1, 10, 0, 0).unwrap();
+        let token = CachedToken::new("user", "secret", 3600, now);
+
+        assert_eq!(Some(token.clone()), CachedToken::from_content(&token.to_content()));
+        assert!(!token.is_expired(now));
+        assert!(token.is_expired(now + chrono::Duration::seconds(3590)));
+        assert_eq!(None, CachedToken::from_content("user\n\n1672567200"));
+    }
+}
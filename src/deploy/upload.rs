@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use crate::command::Error;
+use crate::deploy::archive::object_key;
+use crate::deploy::metadata::{MetadataRules, original_key};
+use crate::deploy::progress::Progress;
+use crate::deploy::selectel::Selectel;
+
+const JOURNAL_TARGET_PREFIX: &str = "target:";
+const JOURNAL_BUILD_PREFIX: &str = "build:";
+const JOURNAL_EXTENSION: &str = "journal";
+
+/// Журнал выгрузки по файлам. Хранит список уже выгруженных файлов, чтобы после обрыва
+/// продолжить выгрузку с места остановки. Файл считается выгруженным, только если с тех пор
+/// у него не изменились размер и время модификации. Журнал относится к одной цели выгрузки
+/// (адрес API, аккаунт и контейнер) и одной директории сборки.
+pub struct UploadJournal {
+    path: PathBuf,
+    uploaded: HashSet<String>,
+}
+
+impl UploadJournal {
+    /// Путь журнала для цели `target` в каталоге `dir`: у каждой цели свой журнал.
+    pub fn path(dir: &Path, target: &str) -> PathBuf {
+        let name = target.replace(|c: char| !c.is_ascii_alphanumeric(), "-");
+        dir.join(format!("{name}.{JOURNAL_EXTENSION}"))
+    }
+
+    /// Открывает журнал выгрузки `build_path` в `target`. Журнал другой цели или директории сборки
+    /// игнорируется и начинается заново.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если журнал не удалось прочитать или создать.
+    pub fn open(path: &Path, target: &str, build_path: &Path) -> Result<Self, Error> {
+        let header = format!("{JOURNAL_TARGET_PREFIX}{target}\n{JOURNAL_BUILD_PREFIX}{}\n", build_path.display());
+        let content = fs::read_to_string(path).unwrap_or_default();
+
+        let uploaded = if let Some(entries) = content.strip_prefix(&header) {
+            entries.lines().map(ToString::to_string).collect()
+        } else {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(Error::CreateFile)?;
+            }
+            fs::write(path, &header).map_err(Error::WriteFile)?;
+            HashSet::new()
+        };
+
+        Ok(Self { path: path.to_path_buf(), uploaded })
+    }
+
+    fn entry(build_path: &Path, relative_path: &Path) -> Result<String, Error> {
+        let metadata = build_path.join(relative_path).metadata().map_err(Error::ReadFile)?;
+        let modified = metadata.modified().map_err(Error::ReadFile)?
+            .duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+
+        Ok(format!("{}\t{}\t{}", object_key(relative_path), metadata.len(), modified))
+    }
+
+    pub fn is_uploaded(&self, entry: &str) -> bool {
+        self.uploaded.contains(entry)
+    }
+
+    /// Отмечает файл выгруженным. Запись сразу попадает на диск.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если не удалось дописать журнал.
+    pub fn record(&mut self, entry: String) -> Result<(), Error> {
+        let mut f = File::options().append(true).open(&self.path).map_err(Error::WriteFile)?;
+        writeln!(f, "{entry}").map_err(Error::WriteFile)?;
+        self.uploaded.insert(entry);
+        Ok(())
+    }
+
+    /// Удаляет журнал после успешной выгрузки всех файлов.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если журнал не удалось удалить.
+    pub fn finish(self) -> Result<(), Error> {
+        fs::remove_file(&self.path).map_err(Error::WriteFile)
+    }
+}
+
+/// Итог выгрузки по файлам.
+#[derive(Debug, Default)]
+pub struct UploadSummary {
+    pub uploaded: usize,
+    pub resumed: usize,
+}
+
+/// Выгружает файлы `files` из `build_path` по одному с заголовками согласно `rules`. Файлы,
+/// выгруженные в прошлый (прерванный) запуск в ту же цель, пропускаются. Журналы хранятся в `journals_dir`.
+///
+/// # Errors
+///
+/// Вернет `Error` если какой-либо файл не удалось выгрузить. Журнал при этом сохраняется.
+pub fn upload_files(client: &Selectel, build_path: &Path, files: &[PathBuf], rules: &MetadataRules, journals_dir: &Path) -> Result<UploadSummary, Error> {
+    let target = client.target();
+    let mut journal = UploadJournal::open(&UploadJournal::path(journals_dir, &target), &target, build_path)?;
+    let mut summary = UploadSummary::default();
+    let keys: HashSet<String> = files.iter().map(|f| object_key(f)).collect();
+    let total = files.iter()
+        .map(|f| build_path.join(f).metadata().map(|m| m.len()).unwrap_or(0))
+        .sum();
+    let progress = Progress::new("upload", total);
+
+    for relative_path in files {
+        let entry = UploadJournal::entry(build_path, relative_path)?;
+        let size = build_path.join(relative_path).metadata().map_err(Error::ReadFile)?.len();
+
+        if journal.is_uploaded(&entry) {
+            summary.resumed += 1;
+            progress.advance(size);
+            continue;
+        }
+
+        let key = object_key(relative_path);
+        let has_original = original_key(&key).is_some_and(|original| keys.contains(original));
+        let headers = rules.headers_for(&key, has_original);
+
+        client.put_object(&key, &build_path.join(relative_path), &headers)?;
+        journal.record(entry)?;
+        summary.uploaded += 1;
+        progress.advance(size);
+    }
+
+    progress.finish();
+    journal.finish()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use crate::deploy::upload::UploadJournal;
+
+    const TARGET_A: &str = "https://api.selcdn.ru/v1 SEL_1/a";
+    const TARGET_B: &str = "https://api.selcdn.ru/v1 SEL_1/b";
+
+    #[test]
+    fn test_journal_resumes_same_target() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("build/index.html").write_str("<html></html>").unwrap();
+        let build_path = dir.child("build").path().to_path_buf();
+        let path = UploadJournal::path(dir.path(), TARGET_A);
+
+        let entry = UploadJournal::entry(&build_path, Path::new("index.html")).unwrap();
+        let mut journal = UploadJournal::open(&path, TARGET_A, &build_path).unwrap();
+        assert!(!journal.is_uploaded(&entry));
+        journal.record(entry.clone()).unwrap();
+
+        let journal = UploadJournal::open(&path, TARGET_A, &build_path).unwrap();
+        assert!(journal.is_uploaded(&entry));
+
+        journal.finish().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_journal_of_another_target_is_ignored() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("build/index.html").write_str("<html></html>").unwrap();
+        dir.child("other/index.html").write_str("<html></html>").unwrap();
+        let build_path = dir.child("build").path().to_path_buf();
+        let entry = UploadJournal::entry(&build_path, Path::new("index.html")).unwrap();
+
+        let path = UploadJournal::path(dir.path(), TARGET_A);
+        assert_ne!(path, UploadJournal::path(dir.path(), TARGET_B));
+        UploadJournal::open(&path, TARGET_A, &build_path).unwrap().record(entry.clone()).unwrap();
+
+        // тот же файл журнала, но другой контейнер или другая сборка
+        assert!(!UploadJournal::open(&path, TARGET_B, &build_path).unwrap().is_uploaded(&entry));
+        UploadJournal::open(&path, TARGET_A, &build_path).unwrap().record(entry.clone()).unwrap();
+        assert!(!UploadJournal::open(&path, TARGET_A, dir.child("other").path()).unwrap().is_uploaded(&entry));
+    }
+}
@@ -0,0 +1,109 @@
+#![allow(clippy::result_large_err)]
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use crate::deploy::retry::RetryPolicy;
+use crate::grow::SOURCE_DIR_NAME;
+
+/// Сравнивает содержимое страницы `url_path` сайта `site_url` с локальным файлом сборки.
+///
+/// # Errors
+///
+/// Вернет описание расхождения, если страница недоступна или ее содержимое отличается от локального.
+pub fn verify_path(site_url: &str, url_path: &str, build_path: &Path, retry: &RetryPolicy) -> Result<(), String> {
+    let local_path = local_file_for(build_path, url_path);
+    let local = fs::read(&local_path)
+        .map_err(|e| format!("local file {} is not readable: {e}", local_path.display()))?;
+
+    let url = format!("{}/{}", site_url.trim_end_matches('/'), url_path.trim_start_matches('/'));
+    let response = retry.run(|| ureq::get(url.as_str()).call()).map_err(|e| e.to_string())?;
+
+    let mut remote = Vec::new();
+    response.into_reader().read_to_end(&mut remote).map_err(|e| e.to_string())?;
+
+    if remote != local {
+        return Err(format!("content differs (local {} bytes, remote {} bytes)", local.len(), remote.len()));
+    }
+
+    Ok(())
+}
+
+/// Адрес файла индекса `index_path` на сайте: путь относительно каталога сборки или, если индекс лежит в
+/// исходниках сайта, относительно `<blog_path>/source`. `None`, если индекс не лежит ни там, ни там.
+pub fn index_url(index_path: &Path, build_path: &Path, blog_path: Option<&Path>) -> Option<String> {
+    let source_path = blog_path.map(|blog_path| blog_path.join(SOURCE_DIR_NAME));
+    let relative = index_path.strip_prefix(build_path).ok()
+        .or(source_path.as_deref().and_then(|source| index_path.strip_prefix(source).ok()))?;
+
+    let segments: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// Возвращает путь до файла сборки, который отдается по адресу `url_path`. Адреса без расширения
+/// считаются директориями, для них отдается `index.html`.
+pub fn local_file_for(build_path: &Path, url_path: &str) -> PathBuf {
+    let path = url_path.split(['?', '#']).next().unwrap_or_default().trim_matches('/');
+    let local = build_path.join(path);
+
+    if path.is_empty() || Path::new(path).extension().is_none() {
+        return local.join("index.html");
+    }
+
+    local
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+    use crate::deploy::retry::RetryPolicy;
+    use crate::deploy::verify::{index_url, local_file_for, verify_path};
+
+    #[test]
+    fn test_local_file_for_url_path() {
+        let build = Path::new("/build");
+
+        assert_eq!(build.join("index.html"), local_file_for(build, "/"));
+        assert_eq!(build.join("ru/posts/title/index.html"), local_file_for(build, "/ru/posts/title/"));
+        assert_eq!(build.join("ru/posts/title/index.html"), local_file_for(build, "/ru/posts/title"));
+        assert_eq!(build.join("index/data.json"), local_file_for(build, "/index/data.json?v=1"));
+    }
+
+    #[test]
+    fn test_index_url() {
+        let (build, blog) = (Path::new("/blog/build"), Path::new("/blog"));
+
+        assert_eq!(Some(String::from("/index/data.json")), index_url(Path::new("/blog/source/index/data.json"), build, Some(blog)));
+        assert_eq!(Some(String::from("/index/data.json")), index_url(Path::new("/blog/build/index/data.json"), build, None));
+        assert_eq!(None, index_url(Path::new("/elsewhere/data.json"), build, Some(blog)));
+    }
+
+    // Сайт отдает устаревший индекс: проверка должна это заметить.
+    #[test]
+    fn test_stale_index_fails_verification() {
+        let build = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(build.path().join("index")).unwrap();
+        std::fs::write(build.path().join("index/data.json"), r#"[{"id": "/ru/posts/new"}]"#).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let site_url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let body = r#"[{"id": "/ru/posts/old"}]"#;
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len()).unwrap();
+        });
+
+        let retry = RetryPolicy::new(1, Duration::from_millis(1));
+        let error = verify_path(&site_url, "/index/data.json", build.path(), &retry).unwrap_err();
+        assert!(error.contains("content differs"), "{error}");
+    }
+}
@@ -2,8 +2,12 @@
 
 pub mod lang;
 pub mod post;
+pub mod preview;
 pub mod serdes;
+pub mod stats;
 pub mod builder;
+pub mod catalog;
+pub mod redirects;
 
 pub const TITLE_FIELD_NAME: &str = "title";
 pub const DESCRIPTION_FIELD_NAME: &str = "description";
@@ -16,6 +20,15 @@ pub const SLUG_FIELD_NAME: &str = "slug";
 pub const SLUG_FIELD_NAME_RU: &str = "slugRu";
 pub const SLUG_FIELD_NAME_EN: &str = "slugEn";
 pub const PUBLISHED_DATE_FIELD_NAME: &str = "published";
+pub const UPDATED_DATE_FIELD_NAME: &str = "updated";
+
+/// Файл переводов grow относительно каталога языка в ABS_TRANSLATIONS_PATH.
+/// Каталог grow сайта, файлы которого доступны от корня сайта: `/static/images/a.png` - это
+/// `source/static/images/a.png`.
+pub const SOURCE_DIR_NAME: &str = "source";
+pub const TRANSLATION_FILE_PATH: &str = "LC_MESSAGES/messages.po";
+/// Картинка записи, если она не задана.
+pub const DEFAULT_IMAGE: &str = "/static/images/default.png";
 
 pub const TRANSLATION_ID_FIELD: &str = "msgid";
 pub const TRANSLATION_VALUE_FIELD: &str = "msgstr";
@@ -27,6 +40,7 @@ pub const POST_TEMPLATE: &str = include_str!("grow/templates/post.tpl");
 pub const TRANSLATION_TEMPLATE: &str = include_str!("grow/templates/translation.tpl");
 pub const DRAFT_TEMPLATE: &str = include_str!("grow/templates/draft_post.tpl");
 pub const TRANSLATION_INDEX_TEMPLATE: &str = include_str!("grow/templates/translation_index.tpl");
+pub const PREVIEW_TEMPLATE: &str = include_str!("grow/templates/preview.tpl");
 pub const RU_EN_MAPPING: &str = include_str!("grow/templates/ru_en_mapping.tpl");
 
 pub const ISO8601_DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
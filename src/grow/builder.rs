@@ -174,6 +174,7 @@ pub struct GrowPostBuilder {
     image: String,
     slug: String,
     published_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
     post_path: PathBuf,
     translation_path: PathBuf,
 }
@@ -222,6 +223,15 @@ impl GrowPostBuilder {
         Ok(self)
     }
 
+    /// Задает дату исправления записи в формате `ISO8601_DATE_TIME_FORMAT`.
+    pub fn updated_at_str(&mut self, updated_at: String) -> Result<&mut Self, Error> {
+        let dt = NaiveDateTime::parse_from_str(updated_at.as_str(), ISO8601_DATE_TIME_FORMAT)
+            .map_err(Error::DateTimeError)?;
+
+        self.updated_at = Some(DateTime::<Utc>::from_utc(dt, Utc));
+        Ok(self)
+    }
+
     /// Задает published_at.
     pub fn published_at(&mut self, published_at: DateTime<Utc>) -> Result<&mut Self, Error> {
         self.published_at = published_at;
@@ -242,7 +252,9 @@ impl BasePostBuilder<GrowPost, GrowPostBuilder> for GrowPostBuilder {
             keywords: self.base_post.keywords.clone(),
             lang: self.base_post.lang,
             published_at: self.published_at,
+            updated_at: self.updated_at,
             slug: self.slug.clone(),
+            image: self.image.clone(),
             text: self.base_post.text.clone(),
         }
     }
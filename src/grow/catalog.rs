@@ -0,0 +1,173 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use chrono::NaiveDate;
+use crate::command::Error;
+use crate::grow::lang::Lang;
+use crate::grow::post::{GrowPost, GrowPostTranslation};
+
+/// Запись вместе с заголовком из перевода.
+#[derive(Debug, Clone)]
+pub struct CatalogPost {
+    pub post: GrowPost,
+    /// Заголовок из `messages.po` языка записи, если перевода нет, то slug.
+    pub title: String,
+}
+
+/// Порядок записей.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum SortBy {
+    /// Сначала новые.
+    #[default]
+    Date,
+    /// По заголовку по алфавиту.
+    Title,
+}
+
+/// Фильтр записей. Пустые поля не фильтруют, строки сравниваются без учета регистра.
+#[derive(Debug, Default, Clone)]
+pub struct PostFilter {
+    pub lang: Option<Lang>,
+    /// Опубликованы не раньше этой даты.
+    pub from: Option<NaiveDate>,
+    /// Опубликованы не позже этой даты.
+    pub to: Option<NaiveDate>,
+    /// Часть одного из ключевых слов.
+    pub keyword: Option<String>,
+    /// Часть имени автора.
+    pub author: Option<String>,
+}
+
+impl PostFilter {
+    pub fn matches(&self, post: &GrowPost) -> bool {
+        let published = post.published_at.date_naive();
+        let contains = |value: &str, part: &str| value.to_lowercase().contains(&part.to_lowercase());
+
+        self.lang.is_none_or(|lang| lang == post.lang)
+            && self.from.is_none_or(|from| published >= from)
+            && self.to.is_none_or(|to| published <= to)
+            && self.keyword.as_ref().is_none_or(|keyword| post.keywords.iter().any(|k| contains(k, keyword)))
+            && self.author.as_ref().is_none_or(|author| contains(&post.author, author))
+    }
+}
+
+/// Все записи блога на языках `langs` с заголовками из переводов. `translation_path` возвращает путь до
+/// `messages.po` языка. Если файла переводов нет, заголовками будут slug.
+///
+/// # Errors
+///
+/// Вернет `Error` если каталог записей не читается или запись не разбирается.
+pub fn load_posts(
+    posts_path: &Path,
+    langs: &[Lang],
+    translation_path: &dyn Fn(Lang) -> Result<PathBuf, Error>,
+) -> Result<Vec<CatalogPost>, Error> {
+    let mut posts = Vec::new();
+
+    for lang in langs {
+        let titles: HashMap<String, String> = GrowPostTranslation::fetch_translations(&translation_path(*lang)?)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| (t.id, t.translated_value))
+            .collect();
+
+        for post in GrowPost::fetch_posts_by_lang(posts_path, *lang)? {
+            let title = titles.get(&post.slug).cloned().unwrap_or(post.slug.clone());
+            posts.push(CatalogPost { post, title });
+        }
+    }
+
+    Ok(posts)
+}
+
+/// Ищет запись со slug `slug` в каталогах языков `langs`. Неразбираемые записи пропускаются.
+///
+/// # Errors
+///
+/// Вернет `Error::PostNotFound` если записи нет и `Error::AmbiguousSlug` если slug есть в нескольких языках.
+pub fn find_post(posts_path: &Path, langs: &[Lang], slug: &str) -> Result<(PathBuf, GrowPost), Error> {
+    let mut found: Vec<(PathBuf, GrowPost)> = Vec::new();
+
+    for lang in langs {
+        for (path, post) in GrowPost::scan_posts_by_lang(posts_path, *lang)? {
+            if let Some(mut post) = post.ok().filter(|post| post.slug == slug) {
+                post.lang = *lang;
+                found.push((path, post));
+            }
+        }
+    }
+
+    match found.len() {
+        0 => Err(Error::PostNotFound(slug.to_string())),
+        1 => Ok(found.remove(0)),
+        _ => Err(Error::AmbiguousSlug(
+            slug.to_string(),
+            found.iter().map(|(_, post)| post.lang.to_lowercase()).collect::<Vec<String>>().join(", "),
+        )),
+    }
+}
+
+/// Сортирует записи, `reverse` меняет порядок на обратный.
+pub fn sort_posts(posts: &mut [CatalogPost], sort_by: SortBy, reverse: bool) {
+    posts.sort_by(|a, b| {
+        let ordering = match sort_by {
+            SortBy::Date => b.post.published_at.cmp(&a.post.published_at),
+            SortBy::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        };
+        let ordering = ordering.then_with(|| a.post.slug.cmp(&b.post.slug));
+        if reverse { ordering.reverse() } else { ordering }
+    });
+}
+
+/// Страница `page` (с 1) по `per_page` записей и количество страниц.
+pub fn page<T>(items: &[T], page: usize, per_page: usize) -> (&[T], usize) {
+    let per_page = per_page.max(1);
+    let pages = items.len().div_ceil(per_page).max(1);
+    let start = (page.max(1) - 1).saturating_mul(per_page).min(items.len());
+    let end = start.saturating_add(per_page).min(items.len());
+
+    (&items[start..end], pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use crate::grow::catalog::{page, sort_posts, CatalogPost, PostFilter, SortBy};
+    use crate::grow::lang::Lang;
+    use crate::grow::post::GrowPost;
+
+    fn post(slug: &str, title: &str, lang: Lang, day: u32) -> CatalogPost {
+        let post = GrowPost {
+            slug: slug.to_string(),
+            lang,
+            author: String::from("Viktor Zharina"),
+            keywords: vec![String::from("rust"), String::from("blog")],
+            published_at: Utc.with_ymd_and_hms(2022, 1, day, 10, 0, 0).unwrap(),
+            ..GrowPost::default()
+        };
+        CatalogPost { post, title: title.to_string() }
+    }
+
+    #[test]
+    fn test_filter_sort_and_page() {
+        let mut posts = vec![post("b", "Beta", Lang::Ru, 2), post("a", "alpha", Lang::En, 1), post("c", "Gamma", Lang::Ru, 3)];
+
+        let filter = PostFilter { lang: Some(Lang::Ru), from: NaiveDate::from_ymd_opt(2022, 1, 3), ..PostFilter::default() };
+        let slugs: Vec<&str> = posts.iter().filter(|p| filter.matches(&p.post)).map(|p| p.post.slug.as_str()).collect();
+        assert_eq!(vec!["c"], slugs);
+
+        let filter = PostFilter { keyword: Some(String::from("RUS")), author: Some(String::from("zharina")), ..PostFilter::default() };
+        assert!(posts.iter().all(|p| filter.matches(&p.post)));
+
+        sort_posts(&mut posts, SortBy::Date, false);
+        assert_eq!(vec!["c", "b", "a"], posts.iter().map(|p| p.post.slug.as_str()).collect::<Vec<&str>>());
+
+        sort_posts(&mut posts, SortBy::Title, false);
+        assert_eq!(vec!["a", "b", "c"], posts.iter().map(|p| p.post.slug.as_str()).collect::<Vec<&str>>());
+
+        let (items, pages) = page(&posts, 2, 2);
+        assert_eq!((1, 2), (items.len(), pages));
+        assert!(page(&posts, 5, 2).0.is_empty());
+    }
+}
@@ -4,18 +4,13 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub enum Lang {
+    #[default]
     Ru,
     En,
 }
 
-impl Default for Lang {
-    fn default() -> Self {
-        Lang::Ru
-    }
-}
-
 impl Display for Lang {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -4,9 +4,10 @@ use std::collections::HashMap;
 use std::fs;
 use crate::command::Error;
 use crate::grow::serdes::{GrowDeserializer, process_template};
-use crate::grow::{AUTHOR_FIELD_NAME, DEFAULT_AUTHOR, DEFAULT_AUTHOR_EN, DESCRIPTION_FIELD_NAME, DRAFT_TEMPLATE, IMAGE_FIELD_NAME, ISO8601_DATE_FORMAT, ISO8601_DATE_TIME_FORMAT, KEYWORDS_DELIMITER, KEYWORDS_FIELD_NAME, LANGUAGE_FIELD_NAME, POST_TEMPLATE, PUBLISHED_DATE_FIELD_NAME, RU_EN_MAPPING, SLUG_FIELD_NAME, TEXT_FIELD_NAME, TITLE_FIELD_NAME, TRANSLATION_TEMPLATE};
+use crate::grow::{AUTHOR_FIELD_NAME, DEFAULT_AUTHOR, DEFAULT_AUTHOR_EN, DEFAULT_IMAGE, DESCRIPTION_FIELD_NAME, DRAFT_TEMPLATE, IMAGE_FIELD_NAME, ISO8601_DATE_FORMAT, ISO8601_DATE_TIME_FORMAT, KEYWORDS_DELIMITER, KEYWORDS_FIELD_NAME, LANGUAGE_FIELD_NAME, POST_TEMPLATE, PUBLISHED_DATE_FIELD_NAME, RU_EN_MAPPING, SLUG_FIELD_NAME, TEXT_FIELD_NAME, TITLE_FIELD_NAME, TRANSLATION_TEMPLATE, UPDATED_DATE_FIELD_NAME};
 use chrono::{DateTime, Utc};
 use std::fs::File;
+use std::fmt::{Display, Formatter};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use regex::{Regex};
@@ -53,9 +54,9 @@ pub struct DraftPost {
 
 /// В Rust миллиард всяких трейтов, которые можно реализовать для вашего типа. Здесь преобразуем
 /// DraftPost в строку согласно шаблону.
-impl ToString for DraftPost {
-    fn to_string(&self) -> String {
-        process_template(DRAFT_TEMPLATE.to_string(), self.as_hashmap())
+impl Display for DraftPost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", process_template(DRAFT_TEMPLATE.to_string(), self.as_hashmap()))
     }
 }
 
@@ -97,6 +98,9 @@ impl DraftPost {
     }
 }
 
+/// Файл записи и результат его разбора.
+pub type PostFile = (PathBuf, Result<GrowPost, Error>);
+
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
 pub struct GrowPost {
     pub title: String,
@@ -105,31 +109,49 @@ pub struct GrowPost {
     pub keywords: Vec<String>,
     pub lang: Lang,
     pub published_at: DateTime<Utc>,
+    /// Дата последнего исправления опубликованной записи.
+    pub updated_at: Option<DateTime<Utc>>,
     pub slug: String,
+    /// Картинка записи, пустая строка - картинка по умолчанию.
+    pub image: String,
     pub text: String,
 }
 
 impl GrowPost {
     pub fn fetch_posts_by_lang(base_posts_path: &Path, lang: Lang) -> Result<Vec<Self>, Error> {
-        let posts_path = fs::read_dir(
-            &base_posts_path.join(lang.to_lowercase())
-        ).map_err(Error::ReadDir)?;
-
-        let mut posts: Vec<Self> = Vec::new();
+        Self::scan_posts_by_lang(base_posts_path, lang)?.into_iter()
+            .map(|(_, post)| post.map(|mut grow_post| {
+                grow_post.lang = lang;
+                grow_post
+            }))
+            .collect()
+    }
 
-        for file in posts_path {
+    /// Читает все записи каталога языка `lang`, отсортированные по имени файла. В отличие от
+    /// `fetch_posts_by_lang` не останавливается на первой ошибке: для каждого файла возвращается запись
+    /// или ошибка чтения/разбора, а язык записи берется из самой записи (`slugRu`/`slugEn`), а не из каталога.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если каталог языка не читается.
+    pub fn scan_posts_by_lang(base_posts_path: &Path, lang: Lang) -> Result<Vec<PostFile>, Error> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for file in fs::read_dir(base_posts_path.join(lang.to_lowercase())).map_err(Error::ReadDir)? {
             let entry_path = file.map_err(Error::ReadFile)?.path();
-
-            if entry_path.is_dir() { continue } // skip directory
-
-            let file_content = fs::read_to_string(&entry_path).map_err(Error::ReadFile)?;
-            //todo detect lang from grow post?
-            let mut grow_post = Self::deserialize(&file_content)?;
-            grow_post.lang = lang;
-            posts.push(grow_post)
+            if !entry_path.is_dir() {
+                paths.push(entry_path);
+            }
         }
-
-        Ok(posts)
+        paths.sort();
+
+        Ok(paths.into_iter()
+            .map(|path| {
+                let post = fs::read_to_string(&path)
+                    .map_err(Error::ReadFile)
+                    .and_then(|content| Self::deserialize(&content));
+                (path, post)
+            })
+            .collect())
     }
 
     fn as_hashmap(&self) -> HashMap<&str, String> {
@@ -137,11 +159,15 @@ impl GrowPost {
             (TITLE_FIELD_NAME, self.title.clone()),
             (AUTHOR_FIELD_NAME, self.author.clone(), ),
             (DESCRIPTION_FIELD_NAME, self.description.clone(), ),
-            (IMAGE_FIELD_NAME, "/static/images/default.png".to_string()),
+            (IMAGE_FIELD_NAME, if self.image.is_empty() { DEFAULT_IMAGE.to_string() } else { self.image.clone() }),
             (LANGUAGE_FIELD_NAME, self.lang.to_string()),
             (SLUG_FIELD_NAME, self.slug.clone()),
             (TEXT_FIELD_NAME, self.text.clone()),
             (PUBLISHED_DATE_FIELD_NAME, self.published_at.format(ISO8601_DATE_TIME_FORMAT).to_string()),
+            // строка с датой исправления есть только у исправленных записей
+            (UPDATED_DATE_FIELD_NAME, self.updated_at.map_or(String::new(), |updated_at| {
+                format!("\n  {UPDATED_DATE_FIELD_NAME}: {}", updated_at.format(ISO8601_DATE_TIME_FORMAT))
+            })),
             (KEYWORDS_FIELD_NAME, self.keywords.join(KEYWORDS_DELIMITER)),
         ]).into_iter().collect()
     }
@@ -156,10 +182,9 @@ impl GrowPost {
 /// Вернет Error при десериализации данных. Meta данные должны быть разделены `META_DELIMITER`, а meta
 /// ключ-значение разделены `KEY_VALUE_DELIMITER`.
 /// Доступные поля `title`, `description`,`keywords`, `lang`, `content`
-
-impl ToString for GrowPost {
-    fn to_string(&self) -> String {
-        process_template(POST_TEMPLATE.to_string(), self.as_hashmap())
+impl Display for GrowPost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", process_template(POST_TEMPLATE.to_string(), self.as_hashmap()))
     }
 }
 
@@ -186,15 +211,38 @@ impl GrowPostTranslation {
 
         Ok(vec)
     }
+
+    /// Заменяет перевод `id` в файле переводов `path` на `replacement` или удаляет его, если `replacement`
+    /// не задан. Остальное содержимое файла не меняется. Возвращает прежнее значение перевода или `None`,
+    /// если перевода `id` в файле нет (тогда файл не перезаписывается).
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не читается или не записывается.
+    pub fn replace_translation(path: &PathBuf, id: &str, replacement: Option<&GrowPostTranslation>) -> Result<Option<String>, Error> {
+        let content = fs::read_to_string(path).map_err(Error::ReadFile)?;
+        let re = Regex::new(&format!(r#"\n?msgid "{}"\nmsgstr "(.*)""#, regex::escape(id))).unwrap();
+
+        let Some(found) = re.captures(&content) else {
+            return Ok(None);
+        };
+        let previous = found[1].to_string();
+
+        let replacement = replacement.map(ToString::to_string).unwrap_or_default();
+        let content = re.replace(&content, regex::NoExpand(&replacement)).to_string();
+        WriterWrapper::write_file(path, &content)?;
+
+        Ok(Some(previous))
+    }
 }
 
 /// Преобразует GrowPostTranslation в форматированную строку для переводов.
 /// title здесь это идентификатор - slug, который будет использован для системы перевода
-impl ToString for GrowPostTranslation {
-    fn to_string(&self) -> String {
-        process_template(TRANSLATION_TEMPLATE.to_string(),
+impl Display for GrowPostTranslation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", process_template(TRANSLATION_TEMPLATE.to_string(),
             HashMap::from([("id", self.id.to_string()), ("value", self.translated_value.to_string())]),
-        )
+        ))
     }
 }
 
@@ -204,6 +252,7 @@ impl WriterWrapper {
     fn _write_to_file(file: &PathBuf, append: bool, file_content: &String) -> Result<(), Error> {
         let mut f = File::options()
             .append(append)
+            .truncate(!append)
             .create(true)
             .write(true)
             .open(file)
@@ -223,6 +272,18 @@ impl WriterWrapper {
 }
 
 impl GrowPost {
+    /// Черновик с содержимым записи и заголовком `title` (в записи вместо заголовка хранится slug,
+    /// а сам заголовок лежит в переводах).
+    pub fn to_draft(&self, title: String) -> DraftPost {
+        DraftPost {
+            title,
+            description: self.description.clone(),
+            keywords: self.keywords.clone(),
+            lang: self.lang,
+            text: self.text.clone(),
+        }
+    }
+
     pub fn build_post_path(&self, posts_path: &Path) -> PathBuf {
         let published_at = self.published_at.format(ISO8601_DATE_FORMAT).to_string();
         let slug = self.slug.clone();
@@ -235,10 +296,25 @@ impl GrowPost {
 #[cfg(test)]
 #[allow(clippy::or_fun_call)]
 mod tests {
+    use chrono::{TimeZone, Utc};
     use crate::grow::{MAX_CHARS_IN_DESCRIPTION, MAX_CHARS_IN_TITLE};
     use crate::grow::serdes::{GrowDeserializer};
     use crate::command::Error::ValueTooLong;
-    use crate::grow::post::{DraftPost, GrowPost};
+    use crate::grow::post::{DraftPost, GrowPost, GrowPostTranslation};
+
+    // Перезапись более коротким содержимым не должна оставлять хвост прежнего файла.
+    #[test]
+    fn test_write_file_overwrites_longer_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("messages.po");
+        std::fs::write(&path, "long previous content").unwrap();
+
+        crate::grow::post::WriterWrapper::write_file(&path, &String::from("short")).unwrap();
+        assert_eq!("short", std::fs::read_to_string(&path).unwrap());
+
+        crate::grow::post::WriterWrapper::write_file_with_append(&path, &String::from(" tail")).unwrap();
+        assert_eq!("short tail", std::fs::read_to_string(&path).unwrap());
+    }
 
     #[test]
     fn test_draft_from_string_conversion_with_default_values() {
@@ -347,4 +423,36 @@ mod tests {
         assert_eq!(default_grow_post.author, grow_post.author);
         assert_eq!(default_grow_post.slug, grow_post.slug);
     }
+
+    #[test]
+    fn test_grow_updated_date_conversion() {
+        let mut post = GrowPost {
+            text: "text".to_string(),
+            published_at: Utc.with_ymd_and_hms(2022, 1, 2, 10, 0, 0).unwrap(),
+            ..GrowPost::default()
+        };
+        assert!(!post.to_string().contains("updated"));
+        assert_eq!(None, GrowPost::deserialize(&post.to_string()).unwrap().updated_at);
+
+        post.updated_at = Some(Utc.with_ymd_and_hms(2022, 2, 3, 11, 0, 0).unwrap());
+        assert!(post.to_string().contains("  published: 2022-01-02 10:00:00\n  updated: 2022-02-03 11:00:00\n"));
+
+        let grow_post = GrowPost::deserialize(&post.to_string()).unwrap();
+        assert_eq!(post.published_at, grow_post.published_at);
+        assert_eq!(post.updated_at, grow_post.updated_at);
+    }
+
+    #[test]
+    fn test_replace_and_remove_translation() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("messages.po");
+        std::fs::write(&path, "# header\nmsgid \"first\"\nmsgstr \"Первая\"\nmsgid \"second\"\nmsgstr \"Вторая\"\n").unwrap();
+
+        let renamed = GrowPostTranslation { id: String::from("renamed"), translated_value: String::from("Новая") };
+        assert_eq!(Some(String::from("Первая")), GrowPostTranslation::replace_translation(&path, "first", Some(&renamed)).unwrap());
+        assert_eq!(Some(String::from("Вторая")), GrowPostTranslation::replace_translation(&path, "second", None).unwrap());
+        assert_eq!(None, GrowPostTranslation::replace_translation(&path, "missing", None).unwrap());
+
+        assert_eq!("# header\nmsgid \"renamed\"\nmsgstr \"Новая\"\n", std::fs::read_to_string(&path).unwrap());
+    }
 }
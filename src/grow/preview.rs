@@ -0,0 +1,91 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::HashMap;
+use crate::grow::post::{DraftPost, GrowPost};
+use crate::grow::serdes::process_template;
+use crate::grow::{DESCRIPTION_FIELD_NAME, ISO8601_DATE_FORMAT, KEYWORDS_DELIMITER, KEYWORDS_FIELD_NAME,
+    LANGUAGE_FIELD_NAME, PREVIEW_TEMPLATE, TEXT_FIELD_NAME, TITLE_FIELD_NAME};
+
+const META_FIELD_NAME: &str = "meta";
+
+/// Html страница предпросмотра черновика.
+pub fn draft_preview(draft: &DraftPost) -> String {
+    render(HashMap::from([
+        (TITLE_FIELD_NAME, escape_html(&draft.title)),
+        (DESCRIPTION_FIELD_NAME, escape_html(&draft.description)),
+        (KEYWORDS_FIELD_NAME, escape_html(&draft.keywords.join(KEYWORDS_DELIMITER))),
+        (LANGUAGE_FIELD_NAME, draft.lang.to_lowercase()),
+        (META_FIELD_NAME, String::from("draft")),
+        (TEXT_FIELD_NAME, text_to_html(&draft.text)),
+    ]))
+}
+
+/// Html страница предпросмотра опубликованной записи.
+pub fn post_preview(post: &GrowPost) -> String {
+    let meta = format!("{}, {}", post.author, post.published_at.format(ISO8601_DATE_FORMAT));
+    render(HashMap::from([
+        (TITLE_FIELD_NAME, escape_html(&post.title)),
+        (DESCRIPTION_FIELD_NAME, escape_html(&post.description)),
+        (KEYWORDS_FIELD_NAME, escape_html(&post.keywords.join(KEYWORDS_DELIMITER))),
+        (LANGUAGE_FIELD_NAME, post.lang.to_lowercase()),
+        (META_FIELD_NAME, escape_html(&meta)),
+        (TEXT_FIELD_NAME, text_to_html(&post.text)),
+    ]))
+}
+
+/// Html страница с ошибкой разбора черновика.
+pub fn error_preview(error: &str) -> String {
+    render(HashMap::from([
+        (TITLE_FIELD_NAME, String::from("Draft has errors")),
+        (DESCRIPTION_FIELD_NAME, String::new()),
+        (KEYWORDS_FIELD_NAME, String::new()),
+        (LANGUAGE_FIELD_NAME, String::from("en")),
+        (META_FIELD_NAME, String::from("draft")),
+        (TEXT_FIELD_NAME, format!("<p class=\"error\">{}</p>", escape_html(error))),
+    ]))
+}
+
+fn render(fields: HashMap<&str, String>) -> String {
+    // текст подставляется последним, чтобы `[title]` внутри текста записи не заменялся
+    let mut fields = fields;
+    let text = fields.remove(TEXT_FIELD_NAME).unwrap_or_default();
+    process_template(PREVIEW_TEMPLATE.to_string(), fields).replace("[text]", &text)
+}
+
+/// Упрощенное преобразование текста записи в html: абзацы разделены пустой строкой,
+/// строки с `#` в начале становятся заголовками.
+pub fn text_to_html(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+        .map(|block| {
+            let level = block.chars().take_while(|c| *c == '#').count();
+            if (1..=6).contains(&level) && block[level..].starts_with(' ') {
+                format!("<h{level}>{}</h{level}>", escape_html(block[level..].trim()))
+            } else {
+                format!("<p>{}</p>", escape_html(block).replace('\n', "<br>\n"))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grow::preview::text_to_html;
+
+    #[test]
+    fn test_text_to_html() {
+        assert_eq!(
+            "<h2>Заголовок</h2>\n<p>первая строка<br>\nвторая &lt;b&gt;</p>\n<p>#хештег</p>",
+            text_to_html("## Заголовок\n\nпервая строка\nвторая <b>\n\n\n#хештег\n")
+        );
+    }
+}
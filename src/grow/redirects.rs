@@ -0,0 +1,226 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::command::Error;
+use crate::grow::lang::Lang;
+use crate::grow::preview::escape_html;
+
+/// Имя файла перенаправлений в каталоге сайта, если `REDIRECTS_FILE` не задан.
+pub const REDIRECTS_FILE_NAME: &str = "redirects.txt";
+/// Расширение файла с правилами nginx, который пишется рядом с файлом перенаправлений.
+pub const NGINX_RULES_EXTENSION: &str = "nginx.conf";
+
+/// Перенаправление со старого адреса записи на новый. Адреса от корня сайта: `/ru/posts/{slug}`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Redirect {
+    pub from: String,
+    pub to: String,
+}
+
+impl Redirect {
+    /// Перенаправление `from` -> `to`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::IncorrectFormat` если адрес не от корня сайта (`/...`), содержит `..` или символы, которые
+    /// ломают правила nginx.
+    pub fn new(from: &str, to: &str) -> Result<Self, Error> {
+        check_url(from)?;
+        check_url(to)?;
+        Ok(Self { from: from.to_string(), to: to.to_string() })
+    }
+}
+
+/// В каком виде выводить перенаправления при сборке.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum RedirectsFormat {
+    /// Страницы с `<meta http-equiv="refresh">` на месте старых адресов в каталоге сборки.
+    #[default]
+    Html,
+    /// Правила `location` для nginx.
+    Nginx,
+}
+
+/// Адрес записи `slug` на языке `lang` от корня сайта.
+pub fn post_url(lang: Lang, slug: &str) -> String {
+    format!("/{}/posts/{slug}", lang.to_lowercase())
+}
+
+/// Перенаправления из файла: по одному на строку, старый и новый адрес через пробел. Пустые строки и
+/// строки с `#` пропускаются.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Redirects {
+    pub redirects: Vec<Redirect>,
+}
+
+impl Redirects {
+    /// Читает перенаправления из файла `path`. Если файла нет, то перенаправлений нет.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не читается или строка не состоит из двух адресов.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).map_err(Error::ReadFile)?;
+        let mut redirects = Vec::new();
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                [from, to] => redirects.push(Redirect::new(from, to).map_err(|e| Error::IncorrectFormat(format!(
+                    "{}: {e}", path.display()
+                )))?),
+                _ => return Err(Error::IncorrectFormat(format!(
+                    "{}: expected `<old url> <new url>`, got `{line}`", path.display()
+                ))),
+            }
+        }
+
+        Ok(Self { redirects })
+    }
+
+    /// Добавляет перенаправление `from` -> `to`. Перенаправления, которые вели на `from`, теперь ведут сразу
+    /// на `to`, чтобы не было цепочек. Перенаправление с `to` удаляется: по этому адресу снова есть запись.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error::IncorrectFormat` если адрес не подходит, см. `Redirect::new`.
+    pub fn add(&mut self, from: &str, to: &str) -> Result<(), Error> {
+        let added = Redirect::new(from, to)?;
+        for redirect in &mut self.redirects {
+            if redirect.to == from {
+                redirect.to = to.to_string();
+            }
+        }
+        self.redirects.retain(|redirect| redirect.from != from && redirect.from != to && redirect.from != redirect.to);
+        self.redirects.push(added);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.redirects.is_empty()
+    }
+
+    /// Сохраняет перенаправления в файл `path`.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если файл не записывается.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let content: String = self.redirects.iter().map(|r| format!("{} {}\n", r.from, r.to)).collect();
+        fs::write(path, content).map_err(Error::WriteFile)
+    }
+
+    /// Правила nginx: старый адрес со слэшем в конце или без него перенаправляется на новый с кодом 301.
+    /// Адреса проверены в `Redirect::new`: в них нет пробелов, кавычек, `;` и скобок, поэтому они не экранируются.
+    pub fn to_nginx(&self) -> String {
+        self.redirects.iter()
+            .map(|r| format!("location ~ ^{}/?$ {{ return 301 {}; }}\n", r.from, r.to))
+            .collect()
+    }
+
+    /// Пишет страницы перенаправлений `<старый адрес>/index.html` в каталог сборки `build_path` и возвращает
+    /// их пути.
+    ///
+    /// # Errors
+    ///
+    /// Вернет `Error` если адрес не подходит (страница записалась бы вне `build_path`) или страница не записывается.
+    pub fn write_html(&self, build_path: &Path) -> Result<Vec<PathBuf>, Error> {
+        let mut pages = Vec::new();
+        for redirect in &self.redirects {
+            check_url(&redirect.from)?;
+            let dir = build_path.join(redirect.from.trim_matches('/'));
+            fs::create_dir_all(&dir).map_err(Error::WriteFile)?;
+
+            let page = dir.join("index.html");
+            fs::write(&page, html_page(&redirect.to)).map_err(Error::WriteFile)?;
+            pages.push(page);
+        }
+        Ok(pages)
+    }
+}
+
+/// Проверяет, что `url` - адрес от корня сайта без `..`, пробелов и символов `"';{}\`.
+fn check_url(url: &str) -> Result<(), Error> {
+    let unsafe_char = url.chars().find(|c| c.is_whitespace() || c.is_control() || "\"';{}\\".contains(*c));
+    if !url.starts_with('/') || url.starts_with("//") || url.split('/').any(|part| part == "..") || unsafe_char.is_some() {
+        return Err(Error::IncorrectFormat(format!("redirect url `{url}` should be a path from the site root without `..`")));
+    }
+    Ok(())
+}
+
+/// Страница, которая сразу перенаправляет на `to` и сообщает поисковикам новый адрес.
+fn html_page(to: &str) -> String {
+    let to = escape_html(to);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta http-equiv=\"refresh\" content=\"0; url={to}\">\n<link rel=\"canonical\" href=\"{to}\">\n</head>\n<body><a href=\"{to}\">{to}</a></body>\n</html>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grow::redirects::{html_page, Redirect, Redirects};
+
+    fn redirect(from: &str, to: &str) -> Redirect {
+        Redirect { from: from.to_string(), to: to.to_string() }
+    }
+
+    #[test]
+    fn test_add_collapses_chains() {
+        let mut redirects = Redirects::default();
+        redirects.add("/ru/posts/a", "/ru/posts/b").unwrap();
+        redirects.add("/ru/posts/b", "/ru/posts/c").unwrap();
+        assert_eq!(vec![redirect("/ru/posts/a", "/ru/posts/c"), redirect("/ru/posts/b", "/ru/posts/c")], redirects.redirects);
+
+        redirects.add("/ru/posts/c", "/ru/posts/a").unwrap();
+        assert_eq!(vec![redirect("/ru/posts/b", "/ru/posts/a"), redirect("/ru/posts/c", "/ru/posts/a")], redirects.redirects);
+
+        assert_eq!(
+            "location ~ ^/ru/posts/b/?$ { return 301 /ru/posts/a; }\nlocation ~ ^/ru/posts/c/?$ { return 301 /ru/posts/a; }\n",
+            redirects.to_nginx()
+        );
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("redirects.txt");
+        assert!(Redirects::load(&path).unwrap().is_empty());
+
+        std::fs::write(&path, "# old new\n/ru/posts/a  /ru/posts/b\n\n").unwrap();
+        let redirects = Redirects::load(&path).unwrap();
+        assert_eq!(vec![redirect("/ru/posts/a", "/ru/posts/b")], redirects.redirects);
+
+        redirects.save(&path).unwrap();
+        assert_eq!("/ru/posts/a /ru/posts/b\n", std::fs::read_to_string(&path).unwrap());
+
+        std::fs::write(&path, "/ru/posts/a\n").unwrap();
+        assert!(Redirects::load(&path).is_err());
+        std::fs::write(&path, "/../../etc /ru/posts/b\n").unwrap();
+        assert!(Redirects::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_urls() {
+        let mut redirects = Redirects::default();
+        for url in ["ru/posts/a", "//evil.example", "/ru/../../etc", "/a;}", "/a\"b"] {
+            assert!(redirects.add(url, "/ru/posts/b").is_err(), "{url}");
+            assert!(redirects.add("/ru/posts/b", url).is_err(), "{url}");
+        }
+        assert!(redirects.is_empty());
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        let outside = Redirects { redirects: vec![redirect("/../outside", "/ru/posts/b")] };
+        assert!(outside.write_html(&dir.path().join("build")).is_err());
+        assert!(!dir.path().join("outside").exists());
+    }
+
+    #[test]
+    fn test_html_page_escapes_url() {
+        let page = html_page("/ru/posts/a?x=1&y=<2>");
+        assert!(page.contains("url=/ru/posts/a?x=1&amp;y=&lt;2&gt;\""), "{page}");
+        assert!(!page.contains("<2>"), "{page}");
+    }
+}
@@ -9,7 +9,7 @@ use crate::grow::builder::{BasePostBuilder};
 use crate::grow::lang::{Lang};
 use crate::grow::{KEY_VALUE_DELIMITER, KEYWORDS_DELIMITER, LF, META_DELIMITER, TEXT_FIELD_NAME, TITLE_FIELD_NAME,
     DESCRIPTION_FIELD_NAME, KEYWORDS_FIELD_NAME, LANGUAGE_FIELD_NAME, AUTHOR_FIELD_NAME, SLUG_FIELD_NAME_RU,
-    SLUG_FIELD_NAME_EN, PUBLISHED_DATE_FIELD_NAME, IMAGE_FIELD_NAME};
+    SLUG_FIELD_NAME_EN, PUBLISHED_DATE_FIELD_NAME, UPDATED_DATE_FIELD_NAME, IMAGE_FIELD_NAME};
 
 pub trait GrowDeserializer<T> {
     fn deserialize(source: &str) -> Result<T, Error>;
@@ -96,8 +96,11 @@ impl GrowDeserializer<GrowPost> for GrowPost {
         for (parameter_name, parameter_value) in map {
             match parameter_name.as_str() {
                 AUTHOR_FIELD_NAME => builder.author(parameter_value)?,
-                SLUG_FIELD_NAME_RU | SLUG_FIELD_NAME_EN => builder.slug(parameter_value)?,
+                // язык записи определяется по ключу slugRu/slugEn
+                SLUG_FIELD_NAME_RU => builder.slug(parameter_value)?.lang(Lang::Ru)?,
+                SLUG_FIELD_NAME_EN => builder.slug(parameter_value)?.lang(Lang::En)?,
                 PUBLISHED_DATE_FIELD_NAME => builder.published_at_str(parameter_value)?,
+                UPDATED_DATE_FIELD_NAME => builder.updated_at_str(parameter_value)?,
                 IMAGE_FIELD_NAME => builder.image(parameter_value)?,
                 // todo fetch title from translation. grow record does not contain title actually or investigate?
                 TITLE_FIELD_NAME => builder.title(parameter_value.clone())?,
@@ -0,0 +1,238 @@
+#![allow(clippy::must_use_candidate)]
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::LazyLock;
+use chrono::NaiveDate;
+use regex::Regex;
+use crate::command::escape_json;
+use crate::grow::ISO8601_DATE_FORMAT;
+use crate::grow::post::GrowPost;
+
+/// Сколько слов в минуту читает читатель, для оценки времени чтения.
+pub const WORDS_PER_MINUTE: usize = 200;
+/// Сколько самых длинных перерывов между записями показывать.
+pub const LONGEST_GAPS_COUNT: usize = 5;
+
+static HTML_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Перерыв между соседними по дате записями.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Gap {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: i64,
+}
+
+/// Статистика по записям блога. Языки хранятся в нижнем регистре в порядке `langs`.
+#[derive(Debug, Default)]
+pub struct PostStats {
+    pub langs: Vec<String>,
+    pub posts: HashMap<String, usize>,
+    /// Количество записей по годам, затем по языкам.
+    pub years: BTreeMap<String, HashMap<String, usize>>,
+    /// Количество записей по месяцам (2022-01), затем по языкам.
+    pub months: BTreeMap<String, HashMap<String, usize>>,
+    pub words: usize,
+    /// Самые частые ключевые слова (в нижнем регистре) и количество записей с ними.
+    pub top_keywords: Vec<(String, usize)>,
+    /// Записи `lang/slug` без перевода, заполняется только по запросу: см. `missing_translations`.
+    pub missing_translations: Vec<String>,
+    pub longest_gaps: Vec<Gap>,
+}
+
+impl PostStats {
+    /// Собирает статистику по записям `posts` на языках `langs`, `top` - сколько ключевых слов показывать.
+    pub fn collect(posts: &[GrowPost], langs: &[String], top: usize) -> Self {
+        let mut stats = PostStats { langs: langs.to_vec(), ..PostStats::default() };
+        let mut keywords: HashMap<String, usize> = HashMap::new();
+
+        for post in posts {
+            let lang = post.lang.to_lowercase();
+            *stats.posts.entry(lang.clone()).or_default() += 1;
+            *stats.years.entry(post.published_at.format("%Y").to_string()).or_default().entry(lang.clone()).or_default() += 1;
+            *stats.months.entry(post.published_at.format("%Y-%m").to_string()).or_default().entry(lang.clone()).or_default() += 1;
+            stats.words += count_words(&post.text);
+
+            let post_keywords: HashSet<String> = post.keywords.iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            for keyword in post_keywords {
+                *keywords.entry(keyword).or_default() += 1;
+            }
+        }
+
+        let mut keywords: Vec<(String, usize)> = keywords.into_iter().collect();
+        keywords.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        keywords.truncate(top);
+        stats.top_keywords = keywords;
+
+        let mut dates: Vec<NaiveDate> = posts.iter().map(|post| post.published_at.date_naive()).collect();
+        dates.sort();
+        let mut gaps: Vec<Gap> = dates.windows(2)
+            .map(|pair| Gap { from: pair[0], to: pair[1], days: (pair[1] - pair[0]).num_days() })
+            .collect();
+        gaps.sort_by(|a, b| b.days.cmp(&a.days).then_with(|| a.from.cmp(&b.from)));
+        gaps.truncate(LONGEST_GAPS_COUNT);
+        stats.longest_gaps = gaps;
+
+        stats
+    }
+
+    pub fn total(&self) -> usize {
+        self.posts.values().sum()
+    }
+
+    /// Среднее количество слов в записи.
+    pub fn average_words(&self) -> usize {
+        self.words.checked_div(self.total()).unwrap_or(0)
+    }
+
+    /// Среднее время чтения записи в минутах, округленное вверх.
+    pub fn average_reading_minutes(&self) -> usize {
+        self.average_words().div_ceil(WORDS_PER_MINUTE)
+    }
+
+    /// Строки отчета в виде (ключ, значение) для вывода через `Details`.
+    pub fn to_lines(&self) -> Vec<(String, String)> {
+        let mut lines = vec![(String::from("posts"), format!("{} ({})", self.total(), self.by_lang(&self.posts)))];
+
+        for (year, posts) in &self.years {
+            lines.push((format!("year {year}"), self.by_lang(posts)));
+        }
+        for (month, posts) in &self.months {
+            lines.push((format!("month {month}"), self.by_lang(posts)));
+        }
+
+        lines.push((String::from("words"), format!("{}, {} per post on average", self.words, self.average_words())));
+        lines.push((String::from("reading_time"), format!("{} min on average", self.average_reading_minutes())));
+
+        for (keyword, count) in &self.top_keywords {
+            lines.push((String::from("keyword"), format!("{keyword} {count}")));
+        }
+        for post in &self.missing_translations {
+            lines.push((String::from("missing_translation"), post.clone()));
+        }
+        for gap in &self.longest_gaps {
+            lines.push((String::from("gap"), format!(
+                "{} days {} - {}", gap.days, gap.from.format(ISO8601_DATE_FORMAT), gap.to.format(ISO8601_DATE_FORMAT)
+            )));
+        }
+
+        lines
+    }
+
+    pub fn to_json(&self) -> String {
+        let periods = |periods: &BTreeMap<String, HashMap<String, usize>>| periods.iter()
+            .map(|(period, posts)| format!(r#""{period}": {}"#, self.by_lang_json(posts)))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let keywords: Vec<String> = self.top_keywords.iter()
+            .map(|(keyword, count)| format!(r#"{{"keyword": "{}", "count": {count}}}"#, escape_json(keyword)))
+            .collect();
+        let missing: Vec<String> = self.missing_translations.iter()
+            .map(|post| format!(r#""{}""#, escape_json(post)))
+            .collect();
+        let gaps: Vec<String> = self.longest_gaps.iter()
+            .map(|gap| format!(
+                r#"{{"from": "{}", "to": "{}", "days": {}}}"#,
+                gap.from.format(ISO8601_DATE_FORMAT), gap.to.format(ISO8601_DATE_FORMAT), gap.days
+            ))
+            .collect();
+
+        format!(
+            r#"{{"posts": {}, "years": {{{}}}, "months": {{{}}}, "words": {}, "average_words": {}, "average_reading_minutes": {}, "top_keywords": [{}], "missing_translations": [{}], "longest_gaps": [{}]}}"#,
+            self.by_lang_json(&self.posts), periods(&self.years), periods(&self.months), self.words, self.average_words(),
+            self.average_reading_minutes(), keywords.join(", "), missing.join(", "), gaps.join(", ")
+        )
+    }
+
+    /// Количество записей по языкам: `ru 2, en 1`.
+    fn by_lang(&self, posts: &HashMap<String, usize>) -> String {
+        self.langs.iter()
+            .map(|lang| format!("{lang} {}", posts.get(lang).unwrap_or(&0)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn by_lang_json(&self, posts: &HashMap<String, usize>) -> String {
+        let counts: Vec<String> = self.langs.iter()
+            .map(|lang| format!(r#""{lang}": {}"#, posts.get(lang).unwrap_or(&0)))
+            .collect();
+        format!("{{{}}}", counts.join(", "))
+    }
+}
+
+/// Записи `lang/slug`, для которых нет записи с тем же slug на другом языке. Записи и их переводы ничем не
+/// связаны, поэтому это только догадка: перевод с другим slug тоже попадет в список.
+pub fn missing_translations(posts: &[GrowPost], langs: &[String]) -> Vec<String> {
+    if langs.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut slugs: HashMap<&str, HashSet<String>> = HashMap::new();
+    for post in posts {
+        slugs.entry(post.slug.as_str()).or_default().insert(post.lang.to_lowercase());
+    }
+
+    let mut missing: Vec<String> = posts.iter()
+        .filter(|post| slugs.get(post.slug.as_str()).is_some_and(|langs| langs.len() < 2))
+        .map(|post| format!("{}/{}", post.lang.to_lowercase(), post.slug))
+        .collect();
+    missing.sort();
+    missing
+}
+
+/// Количество слов в тексте записи без html тэгов.
+pub fn count_words(text: &str) -> usize {
+    HTML_TAG_RE.replace_all(text, " ").split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use crate::grow::lang::Lang;
+    use crate::grow::post::GrowPost;
+    use crate::grow::stats::{count_words, missing_translations, Gap, PostStats};
+
+    fn post(slug: &str, lang: Lang, date: (i32, u32, u32), keywords: &[&str], text: &str) -> GrowPost {
+        GrowPost {
+            slug: slug.to_string(),
+            lang,
+            keywords: keywords.iter().map(ToString::to_string).collect(),
+            published_at: Utc.with_ymd_and_hms(date.0, date.1, date.2, 10, 0, 0).unwrap(),
+            text: text.to_string(),
+            ..GrowPost::default()
+        }
+    }
+
+    #[test]
+    fn test_collect_stats() {
+        let posts = vec![
+            post("rust", Lang::Ru, (2022, 1, 10), &["Rust", "cli"], "<p>раз два</p> три"),
+            post("rust", Lang::En, (2022, 1, 12), &["rust"], "one two three four five"),
+            post("grow", Lang::Ru, (2022, 3, 1), &["grow", "rust "], "<h3>text</h3>"),
+        ];
+        let langs = [String::from("ru"), String::from("en")];
+        let stats = PostStats::collect(&posts, &langs, 2);
+
+        assert_eq!(3, stats.total());
+        assert_eq!(3, count_words("<p>раз два</p> три"));
+        assert_eq!(9, stats.words);
+        assert_eq!(3, stats.average_words());
+        assert_eq!(1, stats.average_reading_minutes());
+        assert_eq!(vec![(String::from("rust"), 3), (String::from("cli"), 1)], stats.top_keywords);
+        assert!(stats.missing_translations.is_empty());
+        assert_eq!(vec![String::from("ru/grow")], missing_translations(&posts, &langs));
+        assert!(missing_translations(&posts, &langs[..1]).is_empty());
+        assert_eq!(
+            Gap { from: NaiveDate::from_ymd_opt(2022, 1, 12).unwrap(), to: NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(), days: 48 },
+            stats.longest_gaps[0]
+        );
+
+        let lines = stats.to_lines();
+        assert!(lines.contains(&(String::from("year 2022"), String::from("ru 2, en 1"))));
+        assert!(lines.contains(&(String::from("month 2022-03"), String::from("ru 1, en 0"))));
+    }
+}
@@ -6,6 +6,6 @@ keywords: [keywords]
 image: [image]
 slug[lang]: [slug]
 $dates:
-  published: [published]
+  published: [published][updated]
 ---
 [text]
\ No newline at end of file
@@ -0,0 +1,20 @@
+<!DOCTYPE html>
+<html lang="[lang]">
+<head>
+<meta charset="utf-8">
+<meta name="description" content="[description]">
+<meta name="keywords" content="[keywords]">
+<title>[title]</title>
+<style>
+body { max-width: 42em; margin: 2em auto; padding: 0 1em; font: 18px/1.6 Georgia, serif; color: #222; }
+.meta { color: #777; font-size: 0.8em; }
+.error { color: #b00; white-space: pre-wrap; }
+</style>
+</head>
+<body>
+<p class="meta">[meta]</p>
+<h1>[title]</h1>
+<p><i>[description]</i></p>
+[text]
+</body>
+</html>
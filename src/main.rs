@@ -1,5 +1,6 @@
 mod command;
 mod config;
+mod deploy;
 mod grow;
 
 use std::env::Args;
@@ -10,7 +11,7 @@ use crate::command::run;
 
 fn main() {
     if dotenv::dotenv().is_err() && dotenv::from_filename("~/.env").is_err() {
-        println!("{}", "Consider to place env variables to ~/.env file".yellow());
+        eprintln!("{}", "Consider to place env variables to ~/.env file".yellow());
     }
 
     let mut args: Args = env::args();
@@ -25,6 +26,9 @@ fn main() {
         }
         Ok(mut result) => {
             println!("{}", result.summarize());
+            if result.is_failed() {
+                process::exit(1);
+            }
         }
     }
 }
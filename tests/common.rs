@@ -9,7 +9,80 @@ pub const TEST_DRAFT_PATH_ARG_KEY: &str = "--draft-path";
 pub const TEST_POSTS_PATH_ARG_KEY: &str = "--posts-path";
 pub const TEST_INDEX_PATH_ARG_KEY: &str = "--index-path";
 pub const TEST_TRANSLATIONS_PATH_ARG_KEY: &str = "--translations-path";
+pub const TEST_HISTORY_PATH_ARG_KEY: &str = "--history-path";
+pub const TEST_BUILD_PATH_ARG_KEY: &str = "--build-path";
+pub const TEST_BLOG_PATH_ARG_KEY: &str = "--blog-path";
+pub const TEST_BUILD_COMMAND_ARG_KEY: &str = "--build-command";
 
 pub const TEST_TMP_INDEX_FILE_NAME: &str = "data.json";
 pub const TEST_TMP_DRAFT_FILE_NAME: &str = "draft.md";
-pub const TEST_EMPTY_CONTENT: &str = "";
\ No newline at end of file
+pub const TEST_EMPTY_CONTENT: &str = "";
+
+/// Содержимое записи grow для тестов: `post_content("slug").lang("En").to_string()`.
+pub struct PostContent<'a> {
+    slug: &'a str,
+    lang: &'a str,
+    author: &'a str,
+    keywords: &'a str,
+    image: &'a str,
+    published: &'a str,
+    text: Option<&'a str>,
+}
+
+/// Русская запись `slug` от 2022-01-02 с описанием `description {slug}` и текстом `<p>text {slug}</p>`.
+pub fn post_content(slug: &str) -> PostContent<'_> {
+    PostContent {
+        slug,
+        lang: "Ru",
+        author: "author",
+        keywords: "k1,k2",
+        image: "/static/images/default.png",
+        published: "2022-01-02 10:00:00",
+        text: None,
+    }
+}
+
+impl<'a> PostContent<'a> {
+    /// Язык для ключа `slug{lang}`: `Ru` или `En`.
+    pub fn lang(mut self, lang: &'a str) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub fn author(mut self, author: &'a str) -> Self {
+        self.author = author;
+        self
+    }
+
+    pub fn keywords(mut self, keywords: &'a str) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    pub fn image(mut self, image: &'a str) -> Self {
+        self.image = image;
+        self
+    }
+
+    pub fn published(mut self, published: &'a str) -> Self {
+        self.published = published;
+        self
+    }
+
+    pub fn text(mut self, text: &'a str) -> Self {
+        self.text = Some(text);
+        self
+    }
+}
+
+impl std::fmt::Display for PostContent<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slug = self.slug;
+        let text = self.text.map_or(format!("<p>text {slug}</p>"), ToString::to_string);
+        write!(
+            f,
+            "---\n$title@: {slug}\nauthor@: {}\ndescription: description {slug}\nkeywords: {}\nimage: {}\nslug{}: {slug}\n$dates:\n  published: {}\n---\n\n{text}\n",
+            self.author, self.keywords, self.image, self.lang, self.published
+        )
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_build_command {
+    use std::fs;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::BUILD_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_BLOG_PATH_ARG_KEY, TEST_BUILD_COMMAND_ARG_KEY, TEST_BUILD_PATH_ARG_KEY};
+
+    fn run(blog: &TempDir, build_command: &str) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .arg(BUILD_COMMAND_NAME)
+            .arg(format!("{}={}", TEST_BLOG_PATH_ARG_KEY, blog.path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, blog.child("build").path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_COMMAND_ARG_KEY, build_command))
+            .output()
+            .unwrap()
+    }
+
+    // Команда выполняется в каталоге блога и обновляет каталог сборки.
+    #[test]
+    fn test_run_build_command() {
+        let blog = TempDir::new().expect("Can't create tmp dir for blog.");
+
+        let output = run(&blog, "mkdir -p build && echo '<p>text</p>' > build/index.html");
+        dbg!(&output);
+        assert!(output.status.success());
+        assert!(blog.child("build/index.html").path().exists());
+    }
+
+    #[test]
+    fn test_run_build_command_fails() {
+        let blog = TempDir::new().expect("Can't create tmp dir for blog.");
+
+        let output = run(&blog, "exit 3");
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("failed"));
+
+        // команда успешна, но ничего не собрала
+        let output = run(&blog, "true");
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("was not refreshed"));
+    }
+
+    // Перенаправления из redirects.txt в каталоге блога выводятся правилами nginx рядом с этим файлом.
+    #[test]
+    fn test_build_emits_nginx_redirects() {
+        let blog = TempDir::new().expect("Can't create tmp dir for blog.");
+        blog.child("redirects.txt").write_str("/ru/posts/old /ru/posts/new\n").unwrap();
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .arg(BUILD_COMMAND_NAME)
+            .arg(format!("{}={}", TEST_BLOG_PATH_ARG_KEY, blog.path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, blog.child("build").path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_COMMAND_ARG_KEY, "mkdir -p build && touch build/index.html"))
+            .arg("--redirects=nginx")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        let rules = fs::read_to_string(blog.child("redirects.nginx.conf").path()).unwrap();
+        assert_eq!("location ~ ^/ru/posts/old/?$ { return 301 /ru/posts/new; }\n", rules);
+        assert!(!blog.child("build/ru/posts/old").path().exists());
+    }
+}
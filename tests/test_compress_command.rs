@@ -0,0 +1,66 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_compress_command {
+    use std::fs::File;
+    use std::io::Read;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+    use flate2::read::GzDecoder;
+
+    use mashinka::command::COMPRESS_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_BUILD_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY};
+
+    fn init() -> TempDir {
+        let build = TempDir::new().expect("Can't create tmp dir for build.");
+        build.child("index.html").write_str(&"<p>text</p>".repeat(200)).unwrap();
+        build.child("static/small.css").write_str("p{}").unwrap();
+        build.child("static/image.png").write_str(&"x".repeat(4096)).unwrap();
+        build
+    }
+
+    fn run(build: &TempDir, extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .arg(COMPRESS_COMMAND_NAME)
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, build.path().to_str().unwrap()))
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    // Сжимается только html: css меньше порога, png не сжимается. Повторный запуск ничего не пересоздает.
+    #[test]
+    fn test_run_compress_command() {
+        let build = init();
+
+        let output = run(&build, &["--brotli"]);
+        dbg!(&output);
+        assert!(output.status.success());
+
+        assert!(build.child("index.html.br").path().exists());
+        assert!(!build.child("static/small.css.gz").path().exists());
+        assert!(!build.child("static/image.png.gz").path().exists());
+
+        let mut content = String::new();
+        GzDecoder::new(File::open(build.child("index.html.gz").path()).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!("<p>text</p>".repeat(200), content);
+
+        let output = run(&build, &["--brotli"]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("compressed:0") && stdout.contains("unchanged:2"));
+    }
+
+    #[test]
+    fn test_run_compress_command_dry_run() {
+        let build = init();
+
+        let output = run(&build, &[TEST_DRY_RUN_ARG_KEY]);
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("index.html.gz"));
+        assert!(!build.child("index.html.gz").path().exists());
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_config_command {
+    use assert_fs::prelude::{FileWriteStr, PathChild, PathCreateDir};
+    use assert_fs::TempDir;
+
+    use mashinka::command::CONFIG_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_BUILD_PATH_ARG_KEY, TEST_INDEX_PATH_ARG_KEY, TEST_POSTS_PATH_ARG_KEY};
+
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("build").create_dir_all().unwrap();
+        dir.child("posts").create_dir_all().unwrap();
+        dir.child("mashinka.toml").write_str(&format!(
+            "default = \"blog\"\n\n[profiles.blog]\nposts_path = \"{}/posts\"\n\n[profiles.blog.deploy]\naccount = \"1\"\nusername = \"user\"\ncontainer = \"blog\"\ndest = \"https://example.com\"\n",
+            dir.path().display()
+        )).unwrap();
+        dir
+    }
+
+    // Переменные окружения очищаются, чтобы настройки брались только из параметров, профиля и переменных теста.
+    fn run(dir: &TempDir, subcommand: &str, envs: &[(&str, &str)], extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .current_dir(dir.path())
+            .env_clear()
+            .env("HOME", dir.path())
+            .envs(envs.iter().copied())
+            .arg(CONFIG_COMMAND_NAME)
+            .arg(subcommand)
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_show_sources_and_masks_secrets() {
+        let dir = init();
+        let build_path = format!("{}={}/build", TEST_BUILD_PATH_ARG_KEY, dir.path().display());
+        let output = run(&dir, "show", &[("DEPLOY_PASSWORD", "top-secret"), ("SERVE_PORT", "9000")], &[&build_path]);
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(&format!("ABS_BUILD_PATH: {}/build (flag --build-path)", dir.path().display())), "{stdout}");
+        assert!(stdout.contains(&format!("ABS_POSTS_PATH: {}/posts (file ", dir.path().display())), "{stdout}");
+        assert!(stdout.contains("SERVE_PORT: 9000 (env)"), "{stdout}");
+        assert!(stdout.contains("COMPRESS_MIN_SIZE: 1024 (default)"), "{stdout}");
+        assert!(stdout.contains("ABS_TRANSLATIONS_PATH: not set"), "{stdout}");
+        assert!(stdout.contains("DEPLOY_SITE_URL: https://example.com (same as DEPLOY_DEST, file "), "{stdout}");
+        assert!(stdout.contains(&format!("DEPLOY_HISTORY_PATH: {}/.cache/mashinka/releases/blog (default)", dir.path().display())), "{stdout}");
+        assert!(stdout.contains("DEPLOY_PASSWORD: ****** (env)"), "{stdout}");
+        assert!(!stdout.contains("top-secret"), "{stdout}");
+
+        // в команде может быть сам секрет, поэтому она не выводится
+        let output = run(&dir, "show", &[("DEPLOY_PASSWORD_COMMAND", "echo top-secret")], &[]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("DEPLOY_PASSWORD: <command output> (command DEPLOY_PASSWORD_COMMAND)"), "{stdout}");
+        assert!(!stdout.contains("top-secret"), "{stdout}");
+    }
+
+    #[test]
+    fn test_check_paths_and_deploy_vars() {
+        let dir = init();
+        let index_path = format!("{}={}/data.json", TEST_INDEX_PATH_ARG_KEY, dir.path().display());
+        let output = run(&dir, "check", &[("DEPLOY_PASSWORD", "top-secret")], &[&index_path]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success(), "{stdout}");
+        assert!(stdout.contains(&format!("ABS_POSTS_PATH: {}/posts ok", dir.path().display())), "{stdout}");
+        assert!(stdout.contains(&format!("ABS_INDEX_FILE: {}/data.json ok", dir.path().display())), "{stdout}");
+        assert!(stdout.contains("deploy: ok"), "{stdout}");
+
+        // нет каталога записей и пароля для выгрузки
+        let posts_path = format!("{}={}/missing", TEST_POSTS_PATH_ARG_KEY, dir.path().display());
+        let output = run(&dir, "check", &[("DEPLOY_RETRIES", "many")], &[&posts_path]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(!output.status.success());
+        assert!(stdout.contains(&format!("ABS_POSTS_PATH: {}/missing is not a dir", dir.path().display())), "{stdout}");
+        assert!(stdout.contains("DEPLOY_RETRIES: `many` is not a non negative number"), "{stdout}");
+        assert!(stdout.contains("deploy: Env variable DEPLOY_PASSWORD is not set"), "{stdout}");
+    }
+}
@@ -0,0 +1,167 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_deploy_command {
+    use std::str::from_utf8;
+    use std::process::Command;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::{DEPLOY_COMMAND_NAME, HISTORY_SUBCOMMAND_NAME, ROLLBACK_SUBCOMMAND_NAME};
+    use crate::common::{BIN_NAME, TEST_BUILD_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY, TEST_HISTORY_PATH_ARG_KEY};
+
+    const RELEASES: [&str; 3] = ["20230101-100000", "20230102-100000", "20230103-100000"];
+
+    fn init() -> TempDir {
+        let history = TempDir::new().expect("Can't create tmp dir for history.");
+
+        for id in RELEASES {
+            history.child(format!("{id}.meta"))
+                .write_str(&format!("id: {id}\ncreated: 2023-01-01 10:00:00\nsize: 10\nbuild_path: /build\n"))
+                .expect("Can't create release meta.");
+            history.child(format!("{id}.tar.gz")).write_str("archive").unwrap();
+        }
+
+        history.child("current").write_str(RELEASES[2]).unwrap();
+        history
+    }
+
+    fn deploy_command() -> Command {
+        let mut command = test_bin::get_test_bin(BIN_NAME);
+        command.arg(DEPLOY_COMMAND_NAME).envs([
+            ("DEPLOY_ACCOUNT", "account"),
+            ("DEPLOY_USERNAME", "user"),
+            ("DEPLOY_PASSWORD", "password"),
+            ("DEPLOY_CONTAINER", "container"),
+            ("DEPLOY_DEST", "https://example.com"),
+        ]);
+        command
+    }
+
+    fn init_build() -> TempDir {
+        let build = TempDir::new().expect("Can't create tmp dir for build.");
+        build.child("index.html").write_str("<html>main</html>").unwrap();
+        build.child("ru/posts/title/index.html").write_str("<html>post</html>").unwrap();
+        // исключаются правилами из .mashinkaignore
+        build.child(".mashinkaignore").write_str(".DS_Store\n*.map\n").unwrap();
+        build.child("ru/.DS_Store").write_str("junk").unwrap();
+        build.child("static/app.js.map").write_str("{}").unwrap();
+        build
+    }
+
+    // Манифест предыдущей выгрузки содержит удаленный файл и измененную главную.
+    #[test]
+    fn test_run_deploy_command_dry_run_report() {
+        let build = init_build();
+        let history = TempDir::new().unwrap();
+        let manifest = history.child("previous.manifest");
+        manifest.write_str("index.html\t1\t00000000\nremoved.html\t1\t00000000\n").unwrap();
+
+        let output = deploy_command()
+            .arg(TEST_DRY_RUN_ARG_KEY)
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, build.path().to_str().unwrap()))
+            .arg(format!("--manifest={}", manifest.path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .arg("--format=json")
+            .output()
+            .unwrap();
+
+        dbg!(&output);
+        assert!(output.status.success());
+
+        let stdout = from_utf8(&output.stdout).unwrap();
+        assert!(stdout.contains(r#""files": 2"#));
+        assert!(stdout.contains(r#""added": ["ru/posts/title/index.html"]"#));
+        assert!(stdout.contains(r#""changed": ["index.html"]"#));
+        assert!(stdout.contains(r#""removed": ["removed.html"]"#));
+    }
+
+    // --dry-run не выполняет команды реквизитов, ему нужен только адрес выгрузки.
+    #[test]
+    fn test_deploy_dry_run_skips_secret_commands() {
+        let build = init_build();
+        let history = TempDir::new().unwrap();
+        let marker = history.child("password-command-ran");
+
+        let output = deploy_command()
+            .env_remove("DEPLOY_PASSWORD")
+            .env("DEPLOY_PASSWORD_COMMAND", format!("touch {} && echo password", marker.path().display()))
+            .arg(TEST_DRY_RUN_ARG_KEY)
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, build.path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(from_utf8(&output.stdout).unwrap().contains("deployed_to:https://example.com"));
+        assert!(!marker.path().exists());
+    }
+
+    #[test]
+    fn test_run_deploy_history_command() {
+        let history = init();
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .args([DEPLOY_COMMAND_NAME, HISTORY_SUBCOMMAND_NAME])
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .output()
+            .unwrap();
+
+        dbg!(&output);
+        assert!(output.status.success());
+
+        let stdout = from_utf8(&output.stdout).unwrap();
+        assert!(RELEASES.iter().all(|id| stdout.contains(id)));
+        let current = stdout.lines().find(|line| line.contains("(current)")).unwrap();
+        assert!(current.contains(RELEASES[2]));
+    }
+
+    // Без --to откатываемся на версию, предшествующую текущей.
+    #[test]
+    fn test_run_deploy_rollback_command_dry_run() {
+        let history = init();
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .args([DEPLOY_COMMAND_NAME, ROLLBACK_SUBCOMMAND_NAME, TEST_DRY_RUN_ARG_KEY])
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .output()
+            .unwrap();
+
+        dbg!(&output);
+        assert!(output.status.success());
+
+        let stdout = from_utf8(&output.stdout).unwrap();
+        assert!(stdout.contains(&format!("rollback_to:{}", RELEASES[1])));
+    }
+
+    #[test]
+    fn fail_deploy_rollback_to_release_of_another_container() {
+        let history = init();
+        history.child(format!("{}.meta", RELEASES[1]))
+            .write_str(&format!("id: {}\ncreated: 2023-01-01 10:00:00\nsize: 10\nbuild_path: /build\ncontainer: other\n", RELEASES[1]))
+            .unwrap();
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .args([DEPLOY_COMMAND_NAME, ROLLBACK_SUBCOMMAND_NAME, TEST_DRY_RUN_ARG_KEY])
+            .env("DEPLOY_CONTAINER", "container")
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(from_utf8(&output.stderr).unwrap().contains("container `other`"));
+    }
+
+    #[test]
+    fn fail_deploy_rollback_to_unknown_release() {
+        let history = init();
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .args([DEPLOY_COMMAND_NAME, ROLLBACK_SUBCOMMAND_NAME, TEST_DRY_RUN_ARG_KEY, "--to=unknown"])
+            .arg(format!("{}={}", TEST_HISTORY_PATH_ARG_KEY, history.path().to_str().unwrap()))
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+    }
+}
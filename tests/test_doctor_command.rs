@@ -0,0 +1,78 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_doctor_command {
+    use assert_fs::prelude::{FileWriteStr, PathChild, PathCreateDir};
+    use assert_fs::TempDir;
+
+    use mashinka::command::DOCTOR_COMMAND_NAME;
+    use crate::common::{post_content, BIN_NAME, TEST_BLOG_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY, TEST_POSTS_PATH_ARG_KEY, TEST_TRANSLATIONS_PATH_ARG_KEY};
+
+    // ru: одна правильная запись, одна с неправильным именем файла, английская запись в ru каталоге,
+    // повтор slug, запись без перевода и отсутствующей картинкой, неразбираемая запись.
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("source/static/images/default.png").write_str("png").unwrap();
+        dir.child("posts/en").create_dir_all().unwrap();
+
+        dir.child("posts/ru/2022-01-02-ok@ru.md").write_str(&post_content("ok").to_string()).unwrap();
+        dir.child("posts/ru/misnamed.md").write_str(&post_content("named").to_string()).unwrap();
+        dir.child("posts/ru/2022-01-02-english@en.md").write_str(&post_content("english").lang("En").to_string()).unwrap();
+        dir.child("posts/ru/copy.md").write_str(&post_content("ok").to_string()).unwrap();
+        dir.child("posts/ru/2022-01-02-lost@ru.md").write_str(&post_content("lost").image("/static/images/lost.png").to_string()).unwrap();
+        dir.child("posts/ru/broken.md").write_str("no front matter").unwrap();
+
+        dir.child("translations/ru/LC_MESSAGES/messages.po")
+            .write_str("msgid \"ok\"\nmsgstr \"ok\"\nmsgid \"named\"\nmsgstr \"named\"\n").unwrap();
+        dir.child("translations/en/LC_MESSAGES/messages.po")
+            .write_str("msgid \"english\"\nmsgstr \"english\"\n").unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, extra: &[&str]) -> (bool, String) {
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru,en")
+            .arg(DOCTOR_COMMAND_NAME)
+            .arg(format!("{TEST_BLOG_PATH_ARG_KEY}={}", dir.path().display()))
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .arg(format!("{TEST_TRANSLATIONS_PATH_ARG_KEY}={}", dir.child("translations").path().display()))
+            .args(extra)
+            .output()
+            .unwrap();
+        (output.status.success(), String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[test]
+    fn test_doctor_reports_all_problems() {
+        let dir = init();
+        let (success, stdout) = run(&dir, &[]);
+
+        assert!(!success);
+        assert!(stdout.contains("front_matter: ru/broken.md: "), "{stdout}");
+        assert!(stdout.contains("file_name: ru/misnamed.md: expected name ru/2022-01-02-named@ru.md"), "{stdout}");
+        assert!(stdout.contains("wrong_lang_dir: ru/2022-01-02-english@en.md: en post in ru dir"), "{stdout}");
+        assert!(stdout.contains("duplicate_slug: ru/copy.md: slug `ok` is already used by ru/2022-01-02-ok@ru.md"), "{stdout}");
+        assert!(stdout.contains("missing_translation: ru/2022-01-02-lost@ru.md: no msgid `lost`"), "{stdout}");
+        assert!(stdout.contains("missing_image: ru/2022-01-02-lost@ru.md: /static/images/lost.png not found"), "{stdout}");
+        assert!(!stdout.contains("2022-01-02-ok@ru.md:"), "{stdout}");
+        // перевод английской записи ищется в en, а не в каталоге ru, где она лежит
+        assert!(!stdout.contains("no msgid `english`"), "{stdout}");
+    }
+
+    #[test]
+    fn test_doctor_fix_renames_files() {
+        let dir = init();
+
+        let (_, stdout) = run(&dir, &["--fix", TEST_DRY_RUN_ARG_KEY]);
+        assert!(stdout.contains("would be renamed to ru/2022-01-02-named@ru.md"), "{stdout}");
+        assert!(dir.child("posts/ru/misnamed.md").path().exists());
+
+        let (success, stdout) = run(&dir, &["--fix"]);
+        assert!(!success, "other problems are not fixable: {stdout}");
+        assert!(stdout.contains("renamed to en/2022-01-02-english@en.md"), "{stdout}");
+        assert!(stdout.contains("not fixed: ru/2022-01-02-ok@ru.md already exists"), "{stdout}");
+        assert!(dir.child("posts/ru/2022-01-02-named@ru.md").path().exists());
+        assert!(dir.child("posts/en/2022-01-02-english@en.md").path().exists());
+        assert!(!dir.child("posts/ru/misnamed.md").path().exists());
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_edit_command {
+    use std::fs;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::EDIT_COMMAND_NAME;
+    use crate::common::{post_content, BIN_NAME, TEST_DRY_RUN_ARG_KEY, TEST_INDEX_PATH_ARG_KEY, TEST_POSTS_PATH_ARG_KEY, TEST_TRANSLATIONS_PATH_ARG_KEY};
+
+    const POST_PATH: &str = "posts/ru/2022-01-02-first@ru.md";
+    const TRANSLATION_PATH: &str = "translations/ru/LC_MESSAGES/messages.po";
+
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child(POST_PATH).write_str(&post_content("first").to_string()).unwrap();
+        dir.child(TRANSLATION_PATH).write_str("\nmsgid \"first\"\nmsgstr \"Первая\"").unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, editor: &str, extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru")
+            .env("TMPDIR", dir.path())
+            .arg(EDIT_COMMAND_NAME)
+            .arg("--slug=first")
+            .arg(format!("--editor={editor}"))
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .arg(format!("{TEST_TRANSLATIONS_PATH_ARG_KEY}={}", dir.child("translations").path().display()))
+            .arg(format!("{TEST_INDEX_PATH_ARG_KEY}={}", dir.child("data.json").path().display()))
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_edit_keeps_published_and_updates_title() {
+        let dir = init();
+        let editor = "sed -i -e s/first/fixed/ -e s/Первая/Исправленная/";
+
+        let output = run(&dir, editor, &[TEST_DRY_RUN_ARG_KEY]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(fs::read_to_string(dir.child(POST_PATH).path()).unwrap().contains("<p>text first</p>"));
+
+        let output = run(&dir, editor, &[]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        let post = fs::read_to_string(dir.child(POST_PATH).path()).unwrap();
+        assert!(post.contains("<p>text fixed</p>") && post.contains("description: description fixed"), "{post}");
+        assert!(post.contains("slugRu: first") && post.contains("  published: 2022-01-02 10:00:00\n  updated: "), "{post}");
+
+        let translations = fs::read_to_string(dir.child(TRANSLATION_PATH).path()).unwrap();
+        assert_eq!("\nmsgid \"first\"\nmsgstr \"Исправленная\"", translations);
+        assert!(dir.child("data.json").path().exists());
+        let drafts = fs::read_dir(dir.path()).unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().starts_with("mashinka-edit-"))
+            .count();
+        assert_eq!(0, drafts);
+    }
+
+    #[test]
+    fn test_edit_title_without_translations_dir() {
+        let dir = init();
+        fs::remove_dir_all(dir.child("translations").path()).unwrap();
+
+        let output = run(&dir, "sed -i s/^title:.*/title:\\ Новая/", &[]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let translations = fs::read_to_string(dir.child(TRANSLATION_PATH).path()).unwrap();
+        assert!(translations.contains("msgid \"first\"\nmsgstr \"Новая\""), "{translations}");
+    }
+
+    #[test]
+    fn test_edit_without_changes() {
+        let dir = init();
+        let output = run(&dir, "true", &[]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8(output.stdout).unwrap().contains("no changes"));
+        assert!(!fs::read_to_string(dir.child(POST_PATH).path()).unwrap().contains("updated"));
+    }
+
+    #[test]
+    fn test_edit_failed_editor_keeps_post() {
+        let dir = init();
+        let output = run(&dir, "false", &[]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("Editor `false` failed"));
+    }
+
+    #[test]
+    fn test_edit_lang_change_keeps_draft() {
+        let dir = init();
+        let output = run(&dir, "sed -i -e s/^lang:.*/lang:\\ en/ -e s/first/fixed/", &[]);
+        assert!(!output.status.success());
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("lang of the post can't be changed from ru to en"), "{stderr}");
+        let draft = fs::read_dir(dir.path()).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.file_name().unwrap().to_string_lossy().starts_with("mashinka-edit-"))
+            .expect("the edited draft must be kept");
+        assert!(stderr.contains(&draft.display().to_string()), "{stderr}");
+        assert!(fs::read_to_string(&draft).unwrap().contains("lang: en"));
+        assert!(fs::read_to_string(dir.child(POST_PATH).path()).unwrap().contains("<p>text first</p>"));
+    }
+}
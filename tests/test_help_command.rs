@@ -3,7 +3,7 @@ mod common;
 #[cfg(test)]
 pub mod test_version_command {
     use std::str::from_utf8;
-    use mashinka::command::{HELP_COMMAND_NAME};
+    use mashinka::command::{HELP_COMMAND_NAME, PUBLISH_COMMAND_NAME};
     use crate::common::{BIN_NAME};
 
     #[test]
@@ -18,5 +18,25 @@ pub mod test_version_command {
         assert!(&stdout.contains("example"), "Check Example");
         assert!(&stdout.contains("miscellaneous"), "Check Miscellaneous part");
     }
-}
 
+    #[test]
+    fn test_run_help_for_command() {
+        let output = test_bin::get_test_bin(BIN_NAME).args([HELP_COMMAND_NAME, "deploy", "rollback"]).output().unwrap();
+
+        assert!(&output.status.success());
+
+        let stdout = from_utf8(&output.stdout).unwrap();
+        assert!(stdout.contains("mashinka deploy rollback"), "Check usage");
+        assert!(stdout.contains("--to=<id>"), "Check flags");
+        assert!(!stdout.contains("--compress"), "Check only command flags are shown");
+    }
+
+    // Опечатка в имени флага не игнорируется, а подсказывается похожий флаг.
+    #[test]
+    fn test_unknown_flag_suggestion() {
+        let output = test_bin::get_test_bin(BIN_NAME).args([PUBLISH_COMMAND_NAME, "--dry_run"]).output().unwrap();
+
+        assert!(!&output.status.success());
+        assert!(from_utf8(&output.stderr).unwrap().contains("Did you mean `--dry-run`?"));
+    }
+}
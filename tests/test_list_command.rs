@@ -0,0 +1,87 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_list_command {
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::LIST_COMMAND_NAME;
+    use crate::common::{post_content, BIN_NAME, TEST_POSTS_PATH_ARG_KEY, TEST_TRANSLATIONS_PATH_ARG_KEY};
+
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("posts/ru/2022-01-10-rust@ru.md").write_str(&post_content("rust").author("Виктор").keywords("rust,cli").published("2022-01-10 10:00:00").to_string()).unwrap();
+        dir.child("posts/ru/2022-03-01-grow@ru.md").write_str(&post_content("grow").author("Виктор").keywords("grow").published("2022-03-01 10:00:00").to_string()).unwrap();
+        dir.child("posts/en/2022-02-01-deploy@en.md").write_str(&post_content("deploy").lang("En").author("Guest").keywords("rust,selectel").published("2022-02-01 10:00:00").to_string()).unwrap();
+        dir.child("translations/ru/LC_MESSAGES/messages.po")
+            .write_str("msgid \"rust\"\nmsgstr \"Про Rust\"\nmsgid \"grow\"\nmsgstr \"Генератор grow\"\n").unwrap();
+        dir.child("translations/en/LC_MESSAGES/messages.po")
+            .write_str("msgid \"deploy\"\nmsgstr \"About deploy\"\n").unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, extra: &[&str]) -> String {
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru,en")
+            .arg(LIST_COMMAND_NAME)
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .arg(format!("{TEST_TRANSLATIONS_PATH_ARG_KEY}={}", dir.child("translations").path().display()))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn slugs(stdout: &str) -> Vec<String> {
+        stdout.lines()
+            .filter(|line| line.starts_with("20"))
+            .map(|line| line.split_whitespace().nth(2).unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_list_sorts_and_filters_posts() {
+        let dir = init();
+
+        let stdout = run(&dir, &[]);
+        assert_eq!(vec!["grow", "deploy", "rust"], slugs(&stdout), "{stdout}");
+        assert!(stdout.contains("2022-03-01  ru    grow    Генератор grow"), "{stdout}");
+        assert!(stdout.contains("page 1 of 1, 3 posts"), "{stdout}");
+
+        assert_eq!(vec!["deploy", "grow", "rust"], slugs(&run(&dir, &["--sort=title"])));
+        assert_eq!(vec!["rust", "grow"], slugs(&run(&dir, &["--lang=ru", "--reverse"])));
+        assert_eq!(vec!["deploy", "rust"], slugs(&run(&dir, &["--keyword=RUST"])));
+        assert_eq!(vec!["deploy"], slugs(&run(&dir, &["--author=guest"])));
+        assert_eq!(vec!["deploy", "rust"], slugs(&run(&dir, &["--from=2022-01-10", "--to=2022-02-01"])));
+
+        let stdout = run(&dir, &["--per-page=2", "--page=2"]);
+        assert_eq!(vec!["rust"], slugs(&stdout));
+        assert!(stdout.contains("page 2 of 2, 3 posts"), "{stdout}");
+    }
+
+    #[test]
+    fn test_list_rejects_zero_page() {
+        let dir = init();
+        for (arg, message) in [("--page=0", "starting from 1"), ("--per-page=0", "greater than 0")] {
+            let output = test_bin::get_test_bin(BIN_NAME)
+                .arg(LIST_COMMAND_NAME)
+                .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+                .arg(arg)
+                .output()
+                .unwrap();
+            assert!(!output.status.success(), "{arg}");
+            assert!(String::from_utf8(output.stderr).unwrap().contains(message), "{arg}");
+        }
+    }
+
+    #[test]
+    fn test_list_json() {
+        let dir = init();
+        let stdout = run(&dir, &["--format=json", "--lang=en"]);
+        assert_eq!(
+            r#"{"page": 1, "pages": 1, "total": 1, "posts": [{"slug": "deploy", "lang": "en", "title": "About deploy", "author": "Guest", "published": "2022-02-01 10:00:00", "keywords": ["rust", "selectel"]}]}"#,
+            stdout.trim()
+        );
+    }
+}
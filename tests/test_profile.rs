@@ -0,0 +1,55 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_profile {
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::COMPRESS_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_BUILD_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY};
+
+    // Профиль выбирается параметром --profile из mashinka.toml в текущем каталоге.
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("personal/index.html").write_str(&"<p>personal</p>".repeat(200)).unwrap();
+        dir.child("personal/about.html").write_str(&"<p>about</p>".repeat(200)).unwrap();
+        dir.child("team/index.html").write_str(&"<p>team</p>".repeat(200)).unwrap();
+        dir.child("mashinka.toml").write_str(&format!(
+            "default = \"personal\"\n\n[profiles.personal]\nbuild_path = \"{0}/personal\"\n\n[profiles.team]\nbuild_path = \"{0}/team\"\n",
+            dir.path().display()
+        )).unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .current_dir(dir.path())
+            .env_remove("ABS_BUILD_PATH")
+            .env_remove("MASHINKA_PROFILE")
+            .arg(COMPRESS_COMMAND_NAME)
+            .arg(TEST_DRY_RUN_ARG_KEY)
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_profile_selection_and_precedence() {
+        let dir = init();
+
+        let stdout = String::from_utf8(run(&dir, &[]).stdout).unwrap();
+        assert!(stdout.contains("compressed:2"), "default profile: {stdout}");
+
+        let stdout = String::from_utf8(run(&dir, &["--profile=team"]).stdout).unwrap();
+        assert!(stdout.contains("compressed:1"), "selected profile: {stdout}");
+
+        // параметр важнее профиля
+        let build_path = format!("{}={}/personal", TEST_BUILD_PATH_ARG_KEY, dir.path().display());
+        let stdout = String::from_utf8(run(&dir, &["--profile=team", &build_path]).stdout).unwrap();
+        assert!(stdout.contains("compressed:2"), "flag over profile: {stdout}");
+
+        let output = run(&dir, &["--profile=work"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("no profile `work`"));
+    }
+}
@@ -0,0 +1,47 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_release_command {
+    use assert_fs::prelude::PathChild;
+    use assert_fs::TempDir;
+
+    use mashinka::command::RELEASE_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_BLOG_PATH_ARG_KEY, TEST_BUILD_COMMAND_ARG_KEY, TEST_BUILD_PATH_ARG_KEY};
+
+    fn run(blog: &TempDir, build_command: &str) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .arg(RELEASE_COMMAND_NAME)
+            .arg("--skip-publish")
+            .arg("--skip-index")
+            .arg("--skip-deploy")
+            .arg(format!("{}={}", TEST_BLOG_PATH_ARG_KEY, blog.path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_PATH_ARG_KEY, blog.child("build").path().to_str().unwrap()))
+            .arg(format!("{}={}", TEST_BUILD_COMMAND_ARG_KEY, build_command))
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_release_command_with_skipped_steps() {
+        let blog = TempDir::new().expect("Can't create tmp dir for blog.");
+
+        let output = run(&blog, "mkdir -p build && touch build/index.html");
+        dbg!(&output);
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("publish:skipped"));
+        assert!(stdout.contains("build:done"));
+        assert!(stdout.contains("deploy:skipped"));
+    }
+
+    // Ошибка шага останавливает релиз и сообщает, на каком шаге он остановился.
+    #[test]
+    fn test_run_release_command_stops_on_failed_step() {
+        let blog = TempDir::new().expect("Can't create tmp dir for blog.");
+
+        let output = run(&blog, "exit 1");
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("Release stopped at `build` step (completed: none)"));
+    }
+}
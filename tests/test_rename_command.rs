@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_rename_command {
+    use std::fs;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::{BUILD_COMMAND_NAME, RENAME_COMMAND_NAME};
+    use crate::common::{post_content, BIN_NAME, TEST_BLOG_PATH_ARG_KEY, TEST_BUILD_COMMAND_ARG_KEY, TEST_BUILD_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY, TEST_INDEX_PATH_ARG_KEY, TEST_POSTS_PATH_ARG_KEY, TEST_TRANSLATIONS_PATH_ARG_KEY};
+
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("posts/ru/2022-01-02-first@ru.md").write_str(&post_content("first").to_string()).unwrap();
+        dir.child("posts/ru/2022-01-02-second@ru.md").write_str(&post_content("second").to_string()).unwrap();
+        dir.child("translations/ru/LC_MESSAGES/messages.po")
+            .write_str("\nmsgid \"first\"\nmsgstr \"Первая\"\nmsgid \"second\"\nmsgstr \"Вторая\"").unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru")
+            .arg(RENAME_COMMAND_NAME)
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .arg(format!("{TEST_TRANSLATIONS_PATH_ARG_KEY}={}", dir.child("translations").path().display()))
+            .arg(format!("{TEST_INDEX_PATH_ARG_KEY}={}", dir.child("data.json").path().display()))
+            .arg(format!("{TEST_BLOG_PATH_ARG_KEY}={}", dir.path().display()))
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rename_post_and_build_redirects() {
+        let dir = init();
+
+        let output = run(&dir, &["--slug=first", "--to=renamed", TEST_DRY_RUN_ARG_KEY]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+        assert!(!dir.child("redirects.txt").path().exists());
+
+        let output = run(&dir, &["--slug=first", "--to=renamed"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        assert!(!dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+        let post = fs::read_to_string(dir.child("posts/ru/2022-01-02-renamed@ru.md").path()).unwrap();
+        assert!(post.contains("slugRu: renamed") && post.contains("published: 2022-01-02 10:00:00"), "{post}");
+
+        let translations = fs::read_to_string(dir.child("translations/ru/LC_MESSAGES/messages.po").path()).unwrap();
+        assert_eq!("\nmsgid \"renamed\"\nmsgstr \"Первая\"\nmsgid \"second\"\nmsgstr \"Вторая\"", translations);
+
+        let index = fs::read_to_string(dir.child("data.json").path()).unwrap();
+        assert!(index.contains("/ru/posts/renamed") && !index.contains("/ru/posts/first"), "{index}");
+
+        let redirects = fs::read_to_string(dir.child("redirects.txt").path()).unwrap();
+        assert_eq!("/ru/posts/first /ru/posts/renamed\n", redirects);
+
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .arg(BUILD_COMMAND_NAME)
+            .arg(format!("{TEST_BLOG_PATH_ARG_KEY}={}", dir.path().display()))
+            .arg(format!("{TEST_BUILD_PATH_ARG_KEY}={}", dir.child("build").path().display()))
+            .arg(format!("{TEST_BUILD_COMMAND_ARG_KEY}=mkdir -p build && touch build/index.html"))
+            .arg("--redirects=html")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let page = fs::read_to_string(dir.child("build/ru/posts/first/index.html").path()).unwrap();
+        assert!(page.contains("<meta http-equiv=\"refresh\" content=\"0; url=/ru/posts/renamed\">"), "{page}");
+    }
+
+    #[test]
+    fn test_rename_to_existing_or_invalid_slug() {
+        let dir = init();
+
+        let output = run(&dir, &["--slug=first", "--to=second"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("post with slug `second`"));
+
+        let output = run(&dir, &["--slug=first", "--to=New Slug"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("new-slug"));
+        assert!(dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+    }
+}
@@ -0,0 +1,47 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_stats_command {
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::STATS_COMMAND_NAME;
+    use crate::common::{post_content, BIN_NAME, TEST_POSTS_PATH_ARG_KEY};
+
+    fn run(dir: &TempDir, extra: &[&str]) -> String {
+        let output = test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru,en")
+            .arg(STATS_COMMAND_NAME)
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_stats_text_and_json() {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("posts/ru/2022-01-10-rust@ru.md").write_str(&post_content("rust").keywords("rust,cli").published("2022-01-10 10:00:00").text("<p>раз два три</p>").to_string()).unwrap();
+        dir.child("posts/ru/2022-03-01-grow@ru.md").write_str(&post_content("grow").keywords("grow,rust").published("2022-03-01 10:00:00").text("один").to_string()).unwrap();
+        dir.child("posts/en/2022-01-11-rust@en.md").write_str(&post_content("rust").lang("En").keywords("rust").published("2022-01-11 10:00:00").text("one two").to_string()).unwrap();
+
+        let stdout = run(&dir, &[]);
+        assert!(stdout.contains("posts: 3 (ru 2, en 1)"), "{stdout}");
+        assert!(stdout.contains("month 2022-01: ru 1, en 1"), "{stdout}");
+        assert!(stdout.contains("words: 6, 2 per post on average"), "{stdout}");
+        assert!(stdout.contains("reading_time: 1 min on average"), "{stdout}");
+        assert!(stdout.contains("keyword: rust 3"), "{stdout}");
+        assert!(!stdout.contains("missing_translation"), "{stdout}");
+        assert!(stdout.contains("gap: 49 days 2022-01-11 - 2022-03-01"), "{stdout}");
+
+        let stdout = run(&dir, &["--missing-translations"]);
+        assert!(stdout.contains("missing_translation: ru/grow"), "{stdout}");
+        assert!(!stdout.contains("missing_translation: ru/rust"), "{stdout}");
+
+        let stdout = run(&dir, &["--format=json", "--top=1"]);
+        assert!(stdout.starts_with(r#"{"posts": {"ru": 2, "en": 1}, "years": {"2022": {"ru": 2, "en": 1}}"#), "{stdout}");
+        assert!(stdout.contains(r#""top_keywords": [{"keyword": "rust", "count": 3}]"#), "{stdout}");
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_unpublish_command {
+    use std::fs;
+    use assert_fs::prelude::{FileWriteStr, PathChild, PathCreateDir};
+    use assert_fs::TempDir;
+
+    use mashinka::command::UNPUBLISH_COMMAND_NAME;
+    use crate::common::{post_content, BIN_NAME, TEST_DRAFT_PATH_ARG_KEY, TEST_DRY_RUN_ARG_KEY, TEST_INDEX_PATH_ARG_KEY, TEST_POSTS_PATH_ARG_KEY, TEST_TRANSLATIONS_PATH_ARG_KEY};
+
+    fn init() -> TempDir {
+        let dir = TempDir::new().expect("Can't create tmp dir.");
+        dir.child("posts/ru/2022-01-02-first@ru.md").write_str(&post_content("first").to_string()).unwrap();
+        dir.child("posts/ru/2022-01-02-second@ru.md").write_str(&post_content("second").to_string()).unwrap();
+        dir.child("drafts").create_dir_all().unwrap();
+        dir.child("translations/ru/LC_MESSAGES/messages.po")
+            .write_str("\nmsgid \"first\"\nmsgstr \"Первая\"\nmsgid \"second\"\nmsgstr \"Вторая\"").unwrap();
+        dir
+    }
+
+    fn run(dir: &TempDir, extra: &[&str]) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .env("BLOG_LANGUAGES", "ru")
+            .arg(UNPUBLISH_COMMAND_NAME)
+            .arg(format!("{TEST_POSTS_PATH_ARG_KEY}={}", dir.child("posts").path().display()))
+            .arg(format!("{TEST_TRANSLATIONS_PATH_ARG_KEY}={}", dir.child("translations").path().display()))
+            .arg(format!("{TEST_INDEX_PATH_ARG_KEY}={}", dir.child("data.json").path().display()))
+            .arg(format!("{TEST_DRAFT_PATH_ARG_KEY}={}", dir.child("drafts").path().display()))
+            .args(extra)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unpublish_archives_post_and_makes_draft() {
+        let dir = init();
+        let archive = format!("--archive={}", dir.child("archive").path().display());
+
+        let output = run(&dir, &["--slug=first", &archive, "--to-draft", TEST_DRY_RUN_ARG_KEY]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+        assert!(!dir.child("data.json").path().exists());
+
+        let output = run(&dir, &["--slug=first", &archive, "--to-draft"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        assert!(!dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+        assert!(dir.child("archive/ru/2022-01-02-first@ru.md").path().exists());
+
+        let translations = fs::read_to_string(dir.child("translations/ru/LC_MESSAGES/messages.po").path()).unwrap();
+        assert_eq!("\nmsgid \"second\"\nmsgstr \"Вторая\"", translations);
+
+        let index = fs::read_to_string(dir.child("data.json").path()).unwrap();
+        assert!(index.contains("/ru/posts/second") && !index.contains("/ru/posts/first"), "{index}");
+
+        let draft = fs::read_to_string(dir.child("drafts/first.md").path()).unwrap();
+        assert!(draft.contains("title: Первая") && draft.contains("description: description first"), "{draft}");
+    }
+
+    #[test]
+    fn test_unpublish_index_failure_names_completed_steps() {
+        let dir = init();
+        dir.child("data.json").create_dir_all().unwrap();
+
+        let output = run(&dir, &["--slug=first"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Unpublish stopped at `index` step (completed: remove, translation)"), "{stderr}");
+        assert!(!dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+    }
+
+    // Язык проверяется по языкам блога, а не по всем известным mashinka.
+    #[test]
+    fn test_unpublish_lang_outside_blog_languages() {
+        let dir = init();
+        let output = run(&dir, &["--slug=first", "--lang=en"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("--lang should be one of ru, got `en`"), "{stderr}");
+        assert!(dir.child("posts/ru/2022-01-02-first@ru.md").path().exists());
+    }
+
+    #[test]
+    fn test_unpublish_unknown_slug() {
+        let dir = init();
+        let output = run(&dir, &["--slug=third"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr).unwrap().contains("Post with slug `third` not found"));
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+#[cfg(test)]
+pub mod test_watch_command {
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    use mashinka::command::WATCH_COMMAND_NAME;
+    use crate::common::{BIN_NAME, TEST_DRAFT_PATH_ARG_KEY};
+
+    const VALID_DRAFT: &str = "---\ntitle: Заголовок\nlang: ru\ndescription: Описание\nkeywords: бумага,А4\n---\n\ntext\n";
+    const INVALID_DRAFT: &str = "---\ntitle: Заголовок\nlang: ru\nauthor: me\n---\n\ntext\n";
+
+    fn run(draft_path: &std::path::Path) -> std::process::Output {
+        test_bin::get_test_bin(BIN_NAME)
+            .arg(WATCH_COMMAND_NAME)
+            .arg("--once")
+            .arg(format!("{}={}", TEST_DRAFT_PATH_ARG_KEY, draft_path.to_str().unwrap()))
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_watch_command_once() {
+        let drafts = TempDir::new().expect("Can't create tmp dir for drafts.");
+        drafts.child("first.md").write_str(VALID_DRAFT).unwrap();
+
+        let output = run(drafts.child("first.md").path());
+        dbg!(&output);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout).unwrap().contains("first.md:OK"));
+    }
+
+    // В каталоге проверяются все .md файлы, ошибка в любом из них завершает команду с ошибкой.
+    #[test]
+    fn test_run_watch_command_once_for_dir() {
+        let drafts = TempDir::new().expect("Can't create tmp dir for drafts.");
+        drafts.child("first.md").write_str(VALID_DRAFT).unwrap();
+        drafts.child("second.md").write_str(INVALID_DRAFT).unwrap();
+        drafts.child("notes.txt").write_str(INVALID_DRAFT).unwrap();
+
+        let output = run(drafts.path());
+        assert!(!output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("first.md:OK"));
+        assert!(stdout.contains("second.md:"));
+        assert!(!stdout.contains("notes.txt"));
+    }
+}